@@ -0,0 +1,382 @@
+use std::path::PathBuf;
+
+use clap::{builder::RangedU64ValueParser, Parser, Subcommand};
+
+use crate::client::{Client, ClientError};
+use crate::constants::{Mode, DB_NAME, DEFAULT_RACE_PORT, MAX_TEST_SECONDS, MAX_TEST_WORDS};
+use crate::ghost::GhostTarget;
+use crate::record::Record;
+
+#[derive(Parser, Debug, Default)]
+#[command(version, about = "typing test cli app")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Run a timed test of the given seconds
+    #[arg(long, value_name = "SECONDS", conflicts_with_all = ["words", "daily"], value_parser = clap::value_parser!(i64).range(1..=MAX_TEST_SECONDS))]
+    pub time: Option<i64>,
+    /// Run a test of the given number of words
+    #[arg(long, value_name = "COUNT", conflicts_with = "daily", value_parser = RangedU64ValueParser::<usize>::new().range(1..=MAX_TEST_WORDS))]
+    pub words: Option<usize>,
+    /// Run the daily challenge, the same words for everyone on the same day
+    #[arg(long)]
+    pub daily: bool,
+    /// Newline separated word list to build paragraphs from
+    #[arg(long, value_name = "PATH")]
+    pub wordlist: Option<PathBuf>,
+    /// Records database to use instead of the one in the config folder
+    #[arg(long, value_name = "PATH", global = true)]
+    pub db: Option<PathBuf>,
+    /// Mix punctuation and capitals into the paragraph
+    #[arg(long)]
+    pub punctuation: bool,
+    /// Mix numbers into the paragraph
+    #[arg(long)]
+    pub numbers: bool,
+    /// Seed for the word picker, the same seed gives the same paragraph
+    #[arg(long, value_name = "N")]
+    pub seed: Option<u64>,
+    /// Race a past run, "best" for the fastest of the mode or a record id
+    #[arg(long, value_name = "best|ID")]
+    pub ghost: Option<GhostTarget>,
+    /// Keep the records of this session in memory instead of saving them
+    #[arg(long)]
+    pub no_save: bool,
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum Command {
+    /// Print a summary of all records
+    Stats,
+    /// Print the latest records
+    History {
+        /// Number of records to print
+        #[arg(short = 'n', long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// Print all records as csv
+    Export,
+    /// Host a race on the local network and join it
+    Host {
+        /// Port to listen on
+        #[arg(short, long, default_value_t = DEFAULT_RACE_PORT)]
+        port: u16,
+        /// Name shown to the other players, defaults to the user name
+        #[arg(long)]
+        name: Option<String>,
+    },
+    /// Join a race hosted with `typefast host`
+    Join {
+        /// Address of the host, e.g. 192.168.1.20:7878
+        addr: String,
+        /// Name shown to the other players, defaults to the user name
+        #[arg(long)]
+        name: Option<String>,
+    },
+}
+
+impl Command {
+    /// Races run in the terminal ui, every other subcommand prints and exits.
+    pub fn is_race(&self) -> bool {
+        matches!(self, Command::Host { .. } | Command::Join { .. })
+    }
+}
+
+impl Cli {
+    /// Returns the mode picked with `--time`, `--words` or `--daily`, if any.
+    pub fn get_mode(&self) -> Option<Mode> {
+        if self.daily {
+            return Some(Mode::Daily);
+        }
+        self.time.map(Mode::Time).or(self.words.map(Mode::Words))
+    }
+
+    /// Splits `--db` into the folder and file name the client opens, defaulting to the
+    /// database in the config folder.
+    pub fn get_db_location(&self, app_config_path: PathBuf) -> (PathBuf, String) {
+        match &self.db {
+            Some(db_path) => {
+                let db_name = db_path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or(DB_NAME.to_string());
+                let db_folder = db_path
+                    .parent()
+                    .map(|parent| parent.to_path_buf())
+                    .unwrap_or_default();
+                (db_folder, db_name)
+            }
+            None => (app_config_path, DB_NAME.to_string()),
+        }
+    }
+}
+
+/// Runs a subcommand against the records database and returns what it prints. A missing
+/// database has no records yet, it isn't created just to be read.
+pub fn run_command(
+    command: &Command,
+    db_folder: PathBuf,
+    db_name: &str,
+) -> Result<String, ClientError> {
+    if !db_folder.join(db_name).exists() {
+        return Ok(format_command(command, &[]));
+    }
+    let mut client = Client::default();
+    client.open_connection(db_folder, db_name)?;
+    client.migrate()?;
+    let records = client.get_records()?;
+    client.close_connection()?;
+    Ok(format_command(command, &records))
+}
+
+/// Formats the output of a subcommand from the stored records.
+pub fn format_command(command: &Command, records: &[Record]) -> String {
+    match command {
+        Command::Stats => format_stats(records),
+        Command::History { limit } => format_history(records, *limit),
+        Command::Export => format_export(records),
+        Command::Host { .. } | Command::Join { .. } => String::new(),
+    }
+}
+
+fn format_stats(records: &[Record]) -> String {
+    if records.is_empty() {
+        return "no records yet\n".to_string();
+    }
+    let count = records.len() as f64;
+    let best = records.iter().max_by_key(|record| record.wpm);
+    let average_wpm = records.iter().map(|record| record.wpm as f64).sum::<f64>() / count;
+    let average_raw = records
+        .iter()
+        .map(|record| record.raw_wpm as f64)
+        .sum::<f64>()
+        / count;
+    let average_accuracy = records.iter().map(|record| record.accuracy).sum::<f64>() / count;
+
+    let mut output = format!("tests: {}\n", records.len());
+    if let Some(best) = best {
+        output += &format!("best: {} wpm ({}, {})\n", best.wpm, best.mode, best.date);
+    }
+    output += &format!("average wpm: {average_wpm:.2}\n");
+    output += &format!("average raw: {average_raw:.2}\n");
+    output += &format!("average accuracy: {average_accuracy:.2}%\n");
+    output
+}
+
+fn format_history(records: &[Record], limit: usize) -> String {
+    records
+        .iter()
+        .rev()
+        .take(limit)
+        .map(|record| {
+            format!(
+                "{}  {:<9} {:>4} wpm  {:>4} raw  {:>6.2}% acc\n",
+                record.date, record.mode, record.wpm, record.raw_wpm, record.accuracy
+            )
+        })
+        .collect()
+}
+
+fn format_export(records: &[Record]) -> String {
+    let mut output = String::from(
+        "id,date,mode,wpm,raw_wpm,cpm,accuracy,consistency,burst_wpm,slowest_word_time,seed,\
+         word_list,punctuation,numbers\n",
+    );
+    for record in records {
+        output += &format!(
+            "{},{},{},{},{},{},{:.2},{:.2},{},{},{},{},{},{}\n",
+            record.id,
+            escape_csv(&record.date),
+            escape_csv(&record.mode),
+            record.wpm,
+            record.raw_wpm,
+            record.cpm,
+            record.accuracy,
+            record.consistency,
+            record.burst_wpm,
+            record.slowest_word_time,
+            record.seed.map(|seed| seed.to_string()).unwrap_or_default(),
+            escape_csv(record.word_list.as_deref().unwrap_or_default()),
+            record.punctuation,
+            record.numbers
+        );
+    }
+    output
+}
+
+/// Quotes a csv field that holds a comma, quote or line break, quotes inside are doubled.
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{TEST_APP_PATH, TEST_DB_NAME};
+    use std::fs;
+
+    fn get_test_records() -> Vec<Record> {
+        vec![
+            Record {
+                id: 1,
+                wpm: 40,
+                raw_wpm: 50,
+                accuracy: 90.0,
+                mode: "time 60".to_string(),
+                date: "2024-01-01 10:00".to_string(),
+                ..Record::default()
+            },
+            Record {
+                id: 2,
+                wpm: 60,
+                raw_wpm: 62,
+                accuracy: 98.5,
+                mode: "words 25".to_string(),
+                date: "2024-01-02 10:00".to_string(),
+                seed: Some(7),
+                word_list: Some("rust".to_string()),
+                numbers: true,
+                ..Record::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let cli = Cli::try_parse_from(["typefast", "--words", "50", "--seed", "7"]).unwrap();
+        assert_eq!(cli.get_mode(), Some(Mode::Words(50)));
+        assert!(!cli.punctuation);
+        assert_eq!(cli.seed, Some(7));
+        assert!(cli.command.is_none());
+
+        let cli = Cli::try_parse_from(["typefast", "history", "-n", "3", "--db", "a.db"]).unwrap();
+        assert_eq!(cli.command, Some(Command::History { limit: 3 }));
+        assert_eq!(cli.db, Some(PathBuf::from("a.db")));
+
+        assert!(Cli::try_parse_from(["typefast", "--time", "30", "--words", "10"]).is_err());
+        assert!(Cli::try_parse_from(["typefast", "--time", "0"]).is_err());
+        assert!(Cli::try_parse_from(["typefast", "--time", "3600"]).is_ok());
+        assert!(Cli::try_parse_from(["typefast", "--time", "3601"]).is_err());
+        assert!(Cli::try_parse_from(["typefast", "--words", "14400"]).is_ok());
+        assert!(Cli::try_parse_from(["typefast", "--words", "99999999999"]).is_err());
+
+        let cli = Cli::try_parse_from(["typefast", "--ghost", "12"]).unwrap();
+        assert_eq!(cli.ghost, Some(GhostTarget::Record(12)));
+        assert!(Cli::try_parse_from(["typefast", "--ghost", "worst"]).is_err());
+
+        let cli = Cli::try_parse_from(["typefast", "--daily"]).unwrap();
+        assert_eq!(cli.get_mode(), Some(Mode::Daily));
+        assert!(Cli::try_parse_from(["typefast", "--daily", "--words", "10"]).is_err());
+
+        let cli = Cli::try_parse_from(["typefast", "host", "--name", "ana"]).unwrap();
+        assert_eq!(
+            cli.command,
+            Some(Command::Host {
+                port: DEFAULT_RACE_PORT,
+                name: Some("ana".to_string())
+            })
+        );
+        assert!(cli.command.is_some_and(|command| command.is_race()));
+        let cli = Cli::try_parse_from(["typefast", "join", "127.0.0.1:7878"]).unwrap();
+        assert_eq!(
+            cli.command,
+            Some(Command::Join {
+                addr: "127.0.0.1:7878".to_string(),
+                name: None
+            })
+        );
+        assert!(Cli::try_parse_from(["typefast", "join"]).is_err());
+        assert!(!Command::Export.is_race());
+
+        let cli = Cli::try_parse_from(["typefast", "--punctuation", "--numbers"]).unwrap();
+        assert!(cli.punctuation);
+        assert!(cli.numbers);
+        assert!(!cli.no_save);
+
+        let cli = Cli::try_parse_from(["typefast", "--no-save"]).unwrap();
+        assert!(cli.no_save);
+    }
+
+    #[test]
+    fn test_get_db_location() {
+        let cli = Cli::default();
+        let location = cli.get_db_location(PathBuf::from("config"));
+        assert_eq!(location, (PathBuf::from("config"), DB_NAME.to_string()));
+
+        let cli = Cli {
+            db: Some(PathBuf::from("some/folder/records.db")),
+            ..Cli::default()
+        };
+        let location = cli.get_db_location(PathBuf::from("config"));
+        assert_eq!(
+            location,
+            (PathBuf::from("some/folder"), "records.db".to_string())
+        );
+    }
+
+    #[test]
+    fn test_run_command() {
+        // run against a copy so the migrations don't rewrite the fixture
+        let db_name = format!("cli_copy_{TEST_DB_NAME}");
+        let db_path = PathBuf::from(TEST_APP_PATH).join(&db_name);
+        let result = fs::copy(
+            PathBuf::from(TEST_APP_PATH).join(format!("cli_{TEST_DB_NAME}")),
+            &db_path,
+        );
+        assert!(result.is_ok());
+
+        let result = run_command(&Command::Export, PathBuf::from(TEST_APP_PATH), &db_name);
+        assert!(result.is_ok());
+        assert!(result.unwrap().starts_with("id,date,mode"));
+        let result = fs::remove_file(&db_path);
+        assert!(result.is_ok());
+
+        // a fresh install has no database yet and none is created
+        let result = run_command(&Command::Stats, PathBuf::from("idk/idk"), &db_name);
+        assert_eq!(result.unwrap(), "no records yet\n");
+        assert!(!PathBuf::from("idk").exists());
+    }
+
+    #[test]
+    fn test_format_command() {
+        let records = get_test_records();
+
+        let stats = format_command(&Command::Stats, &records);
+        assert!(stats.contains("tests: 2"));
+        assert!(stats.contains("best: 60 wpm (words 25"));
+        assert!(stats.contains("average wpm: 50.00"));
+        assert!(stats.contains("average accuracy: 94.25%"));
+        assert_eq!(format_command(&Command::Stats, &[]), "no records yet\n");
+
+        let history = format_command(&Command::History { limit: 1 }, &records);
+        assert_eq!(history.lines().count(), 1);
+        assert!(history.starts_with("2024-01-02 10:00  words 25"));
+
+        let export = format_command(&Command::Export, &records);
+        let lines = export.lines().collect::<Vec<&str>>();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[2].ends_with(",7,rust,false,true"));
+        assert!(lines[0].starts_with("id,date,mode,wpm"));
+        assert_eq!(
+            lines[1],
+            "1,2024-01-01 10:00,time 60,40,50,0,90.00,0.00,0,0,,,false,false"
+        );
+
+        // commas and quotes in fields keep the columns in place
+        let record = Record {
+            mode: "words 25".to_string(),
+            word_list: Some("nouns, \"verbs\"".to_string()),
+            ..Record::default()
+        };
+        let export = format_command(&Command::Export, &[record]);
+        assert!(export
+            .lines()
+            .nth(1)
+            .unwrap()
+            .ends_with(",\"nouns, \"\"verbs\"\"\",false,false"));
+    }
+}
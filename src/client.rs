@@ -1,9 +1,52 @@
 use std::path::PathBuf;
 
-use rusqlite::{Connection, Error as RusqliteError, Result};
+use rusqlite::{ffi, Connection, Error as RusqliteError, Params, Result};
 use thiserror::Error;
 
+use crate::constants::RecordColumn;
+use crate::keystroke::Keystroke;
+use crate::profile::Profile;
 use crate::record::Record;
+use crate::store::{RecordQuery, RecordStore};
+
+/// Ordered schema migrations, `PRAGMA user_version` stores how many of them were applied.
+/// Only ever append to this list, released steps must stay untouched.
+const MIGRATIONS: [&str; 9] = [
+    "CREATE TABLE IF NOT EXISTS records (
+        id INTEGER NOT NULL PRIMARY KEY,
+        wpm INTEGER NOT NULL,
+        cpm INTEGER NOT NULL,
+        date TEXT NOT NULL
+    );",
+    "ALTER TABLE records ADD COLUMN raw_wpm INTEGER NOT NULL DEFAULT 0;
+     ALTER TABLE records ADD COLUMN accuracy REAL NOT NULL DEFAULT 0;
+     ALTER TABLE records ADD COLUMN mode TEXT NOT NULL DEFAULT 'time 60';",
+    "CREATE TABLE IF NOT EXISTS keystrokes (
+        id INTEGER NOT NULL PRIMARY KEY,
+        record_id INTEGER NOT NULL REFERENCES records(id) ON DELETE CASCADE,
+        timestamp INTEGER NOT NULL,
+        expected TEXT,
+        typed TEXT NOT NULL,
+        is_correct INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS keystrokes_record_id ON keystrokes(record_id);",
+    "ALTER TABLE records ADD COLUMN consistency REAL NOT NULL DEFAULT 0;
+     ALTER TABLE records ADD COLUMN burst_wpm INTEGER NOT NULL DEFAULT 0;
+     ALTER TABLE records ADD COLUMN slowest_word_time INTEGER NOT NULL DEFAULT 0;",
+    "ALTER TABLE records ADD COLUMN quote_id INTEGER;",
+    "ALTER TABLE records ADD COLUMN seed INTEGER;",
+    "CREATE TABLE IF NOT EXISTS players (
+        id INTEGER NOT NULL PRIMARY KEY,
+        name TEXT NOT NULL UNIQUE
+    );
+    ALTER TABLE records ADD COLUMN player_id INTEGER REFERENCES players(id);",
+    "CREATE INDEX IF NOT EXISTS records_player_id ON records(player_id);
+     CREATE INDEX IF NOT EXISTS records_date ON records(date);
+     CREATE INDEX IF NOT EXISTS records_wpm ON records(wpm);",
+    "ALTER TABLE records ADD COLUMN word_list TEXT;
+     ALTER TABLE records ADD COLUMN punctuation INTEGER NOT NULL DEFAULT 0;
+     ALTER TABLE records ADD COLUMN numbers INTEGER NOT NULL DEFAULT 0;",
+];
 
 #[derive(Debug, Default)]
 pub struct Client {
@@ -18,12 +61,34 @@ pub enum ClientError {
     OpenConnectionError(RusqliteError),
     #[error("Could not close connection")]
     CloseConnectionError(),
-    #[error("Could not create records table: {0}")]
-    CreateRecordsTableError(RusqliteError),
+    #[error("Could not read database schema version: {0}")]
+    GetSchemaVersionError(RusqliteError),
+    #[error("Database schema version {0} is newer than the supported version {1}, please update typefast")]
+    UnsupportedSchemaVersionError(usize, usize),
+    #[error("Could not apply database migration {0}: {1}")]
+    MigrationError(usize, RusqliteError),
     #[error("Could not get records")]
     GetRecordsError(),
     #[error("Could not insert record: {0}")]
     InsertRecordError(RusqliteError),
+    #[error("Could not insert keystrokes: {0}")]
+    InsertKeystrokesError(RusqliteError),
+    #[error("Could not get keystrokes: {0}")]
+    GetKeystrokesError(RusqliteError),
+    #[error("Could not get quote personal best: {0}")]
+    GetQuoteBestError(RusqliteError),
+    #[error("Could not get players: {0}")]
+    GetPlayersError(RusqliteError),
+    #[error("Could not insert player: {0}")]
+    InsertPlayerError(RusqliteError),
+    #[error("Could not get player bests: {0}")]
+    GetPlayerBestsError(RusqliteError),
+    #[error("Profile {0} already exists")]
+    ProfileExistsError(String),
+    #[error("Could not count records: {0}")]
+    CountRecordsError(RusqliteError),
+    #[error("Could not delete record: {0}")]
+    DeleteRecordError(RusqliteError),
     #[error("Could not drop records table: {0}")]
     DropRecordsTableError(RusqliteError),
 }
@@ -45,6 +110,10 @@ impl Client {
 
         match Connection::open(app_config_path) {
             Ok(connection) => {
+                // keystrokes are removed together with their record
+                connection
+                    .pragma_update(None, "foreign_keys", true)
+                    .map_err(ClientError::OpenConnectionError)?;
                 self.connection = Some(connection);
                 Ok(())
             }
@@ -61,41 +130,114 @@ impl Client {
         }
     }
 
-    pub fn create_records_table(&self) -> Result<usize, ClientError> {
-        let query = "CREATE TABLE IF NOT EXISTS records (
-                     id INTEGER NOT NULL PRIMARY KEY,
-                     wpm INTEGER NOT NULL,
-                     cpm INTEGER NOT NULL,
-                     date TEXT NOT NULL
-                    );";
-        let connection = self.get_connection();
-
-        match connection {
-            Ok(c) => {
-                let result = c.execute(query, []);
-                match result {
-                    Ok(r) => Ok(r),
-                    // How do i test here
-                    Err(e) => Err(ClientError::CreateRecordsTableError(e)),
-                }
-            }
-            Err(e) => Err(e),
+    pub fn get_schema_version(&self) -> Result<usize, ClientError> {
+        self.get_connection()?
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .map_err(ClientError::GetSchemaVersionError)
+    }
+
+    /// Brings the database up to the latest schema, applying every pending migration in
+    /// its own transaction so a failing step leaves the previous version intact.
+    pub fn migrate(&self) -> Result<(), ClientError> {
+        let connection = self.get_connection()?;
+        let schema_version = self.get_schema_version()?;
+        if schema_version > MIGRATIONS.len() {
+            return Err(ClientError::UnsupportedSchemaVersionError(
+                schema_version,
+                MIGRATIONS.len(),
+            ));
         }
+
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(schema_version) {
+            let version = index + 1;
+            let apply = || -> Result<(), RusqliteError> {
+                let transaction = connection.unchecked_transaction()?;
+                transaction.execute_batch(migration)?;
+                transaction.pragma_update(None, "user_version", version)?;
+                transaction.commit()
+            };
+            apply().map_err(|e| ClientError::MigrationError(version, e))?;
+        }
+        Ok(())
     }
 
     pub fn get_records(&self) -> Result<Vec<Record>, ClientError> {
-        let mut stmt =
-            if let Ok(statement) = self.get_connection()?.prepare("SELECT * FROM records") {
-                statement
-            } else {
-                return Err(ClientError::GetRecordsError());
-            };
-        let rows = if let Ok(rows) = stmt.query_map([], |row| {
+        self.query_records("", [])
+    }
+
+    /// Returns the records saved under the player.
+    pub fn get_player_records(&self, player_id: i32) -> Result<Vec<Record>, ClientError> {
+        self.query_records("WHERE player_id = ?1", [player_id])
+    }
+
+    /// Returns a page of the records, ties are sorted by id in the same direction.
+    pub fn get_record_page(&self, query: &RecordQuery) -> Result<Vec<Record>, ClientError> {
+        // the column names come from `RecordColumn`, never from input
+        let direction = if query.ascending { "ASC" } else { "DESC" };
+        let order = match query.column {
+            // modes sort by kind and then by their number, "time 15" before "time 120"
+            RecordColumn::Mode => format!(
+                "substr(mode, 1, instr(mode || ' ', ' ')) {direction},
+                 CAST(substr(mode, instr(mode || ' ', ' ') + 1) AS INTEGER) {direction},
+                 mode {direction}"
+            ),
+            column => format!("{column} {direction}"),
+        };
+        self.query_records(
+            &format!(
+                "WHERE ?1 IS NULL OR player_id = ?1
+                 ORDER BY {order}, id {direction} LIMIT ?2 OFFSET ?3"
+            ),
+            (query.player_id, query.limit as i64, query.offset as i64),
+        )
+    }
+
+    /// Counts the records of the player, or of everyone.
+    pub fn count_records(&self, player_id: Option<i32>) -> Result<usize, ClientError> {
+        self.get_connection()?
+            .query_row(
+                "SELECT COUNT(*) FROM records WHERE ?1 IS NULL OR player_id = ?1",
+                [player_id],
+                |row| row.get(0),
+            )
+            .map_err(ClientError::CountRecordsError)
+    }
+
+    /// Selects the records matching the condition, e.g. a `WHERE` clause.
+    fn query_records<P: Params>(
+        &self,
+        condition: &str,
+        params: P,
+    ) -> Result<Vec<Record>, ClientError> {
+        let mut stmt = if let Ok(statement) = self.get_connection()?.prepare(&format!(
+            "SELECT id, wpm, cpm, date, raw_wpm, accuracy, mode,
+                        consistency, burst_wpm, slowest_word_time, quote_id, seed, player_id,
+                        word_list, punctuation, numbers
+                 FROM records {condition}"
+        )) {
+            statement
+        } else {
+            return Err(ClientError::GetRecordsError());
+        };
+        let rows = if let Ok(rows) = stmt.query_map(params, |row| {
             Ok(Record {
                 id: row.get(0)?,
                 wpm: row.get(1)?,
                 cpm: row.get(2)?,
                 date: row.get(3)?,
+                raw_wpm: row.get(4)?,
+                accuracy: row.get(5)?,
+                mode: row.get(6)?,
+                consistency: row.get(7)?,
+                burst_wpm: row.get(8)?,
+                slowest_word_time: row.get(9)?,
+                quote_id: row.get(10)?,
+                // sqlite integers are signed, seeds keep their bits
+                seed: row.get::<_, Option<i64>>(11)?.map(|seed| seed as u64),
+                player_id: row.get(12)?,
+                word_list: row.get(13)?,
+                punctuation: row.get(14)?,
+                numbers: row.get(15)?,
             })
         }) {
             rows
@@ -114,26 +256,254 @@ impl Client {
         Ok(records)
     }
 
-    pub fn create_record(&self, wpm: i64, cpm: i64, date: String) -> Result<usize, ClientError> {
-        self.get_connection()?
+    /// Inserts the record and returns its id.
+    pub fn create_record(&self, record: &Record) -> Result<i64, ClientError> {
+        let connection = self.get_connection()?;
+        connection
             .execute(
-                "INSERT INTO records (wpm, cpm, date) VALUES(?1, ?2, ?3)",
-                (wpm, cpm, date),
+                "INSERT INTO records (wpm, cpm, date, raw_wpm, accuracy, mode,
+                                      consistency, burst_wpm, slowest_word_time, quote_id, seed,
+                                      player_id, word_list, punctuation, numbers)
+                 VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+                (
+                    record.wpm,
+                    record.cpm,
+                    &record.date,
+                    record.raw_wpm,
+                    record.accuracy,
+                    &record.mode,
+                    record.consistency,
+                    record.burst_wpm,
+                    record.slowest_word_time,
+                    record.quote_id,
+                    record.seed.map(|seed| seed as i64),
+                    record.player_id,
+                    &record.word_list,
+                    record.punctuation,
+                    record.numbers,
+                ),
             )
-            .map_err(ClientError::InsertRecordError)
+            .map_err(ClientError::InsertRecordError)?;
+        Ok(connection.last_insert_rowid())
     }
 
-    pub fn drop_records_table(&self) -> Result<usize, ClientError> {
+    /// Removes the record, its keystrokes go with it.
+    pub fn delete_record(&self, record_id: i32) -> Result<(), ClientError> {
         self.get_connection()?
-            .execute("DROP TABLE IF EXISTS records", [])
+            .execute("DELETE FROM records WHERE id = ?1", [record_id])
+            .map_err(ClientError::DeleteRecordError)?;
+        Ok(())
+    }
+
+    /// Returns the best wpm the player typed on the quote, if they typed it before. Guests
+    /// compare against the records saved without a player.
+    pub fn get_quote_best(
+        &self,
+        quote_id: i32,
+        player_id: Option<i32>,
+    ) -> Result<Option<i64>, ClientError> {
+        self.get_connection()?
+            .query_row(
+                "SELECT MAX(wpm) FROM records WHERE quote_id = ?1 AND player_id IS ?2",
+                (quote_id, player_id),
+                |row| row.get(0),
+            )
+            .map_err(ClientError::GetQuoteBestError)
+    }
+
+    pub fn get_profiles(&self) -> Result<Vec<Profile>, ClientError> {
+        let mut statement = self
+            .get_connection()?
+            .prepare("SELECT id, name FROM players ORDER BY name")
+            .map_err(ClientError::GetPlayersError)?;
+        let rows = statement
+            .query_map([], |row| {
+                Ok(Profile {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                })
+            })
+            .map_err(ClientError::GetPlayersError)?;
+
+        rows.collect::<Result<Vec<Profile>, RusqliteError>>()
+            .map_err(ClientError::GetPlayersError)
+    }
+
+    /// Inserts a player and returns its profile, names are unique.
+    pub fn create_profile(&self, name: &str) -> Result<Profile, ClientError> {
+        let connection = self.get_connection()?;
+        connection
+            .execute("INSERT INTO players (name) VALUES(?1)", [name])
+            .map_err(|e| match e {
+                RusqliteError::SqliteFailure(error, _)
+                    if error.extended_code == ffi::SQLITE_CONSTRAINT_UNIQUE =>
+                {
+                    ClientError::ProfileExistsError(name.to_string())
+                }
+                e => ClientError::InsertPlayerError(e),
+            })?;
+        Ok(Profile {
+            id: connection.last_insert_rowid() as i32,
+            name: name.to_string(),
+        })
+    }
+
+    /// Returns the best wpm of every player with records, fastest first.
+    pub fn get_player_bests(&self) -> Result<Vec<(String, i64)>, ClientError> {
+        let mut statement = self
+            .get_connection()?
+            .prepare(
+                "SELECT players.name, MAX(records.wpm) AS best FROM records
+                 JOIN players ON players.id = records.player_id
+                 GROUP BY players.id ORDER BY best DESC, players.name",
+            )
+            .map_err(ClientError::GetPlayerBestsError)?;
+        let rows = statement
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(ClientError::GetPlayerBestsError)?;
+
+        rows.collect::<Result<Vec<(String, i64)>, RusqliteError>>()
+            .map_err(ClientError::GetPlayerBestsError)
+    }
+
+    pub fn create_keystrokes(
+        &self,
+        record_id: i64,
+        keystrokes: &[Keystroke],
+    ) -> Result<(), ClientError> {
+        let connection = self.get_connection()?;
+        let insert = || -> Result<(), RusqliteError> {
+            let transaction = connection.unchecked_transaction()?;
+            {
+                let mut statement = transaction.prepare(
+                    "INSERT INTO keystrokes (record_id, timestamp, expected, typed, is_correct)
+                     VALUES(?1, ?2, ?3, ?4, ?5)",
+                )?;
+                for keystroke in keystrokes {
+                    statement.execute((
+                        record_id,
+                        keystroke.timestamp,
+                        &keystroke.expected,
+                        keystroke.typed.to_string(),
+                        keystroke.is_correct,
+                    ))?;
+                }
+            }
+            transaction.commit()
+        };
+        insert().map_err(ClientError::InsertKeystrokesError)
+    }
+
+    pub fn get_keystrokes(&self, record_id: i32) -> Result<Vec<Keystroke>, ClientError> {
+        let mut statement = self
+            .get_connection()?
+            .prepare(
+                "SELECT id, timestamp, expected, typed, is_correct FROM keystrokes
+                 WHERE record_id = ?1 ORDER BY id",
+            )
+            .map_err(ClientError::GetKeystrokesError)?;
+        let rows = statement
+            .query_map([record_id], |row| {
+                let typed: String = row.get(3)?;
+                Ok(Keystroke {
+                    id: row.get(0)?,
+                    timestamp: row.get(1)?,
+                    expected: row.get(2)?,
+                    typed: typed.chars().next().unwrap_or_default(),
+                    is_correct: row.get(4)?,
+                })
+            })
+            .map_err(ClientError::GetKeystrokesError)?;
+
+        rows.collect::<Result<Vec<Keystroke>, RusqliteError>>()
+            .map_err(ClientError::GetKeystrokesError)
+    }
+
+    pub fn drop_records_table(&self) -> Result<(), ClientError> {
+        // resetting the version lets the next migration recreate the table
+        self.get_connection()?
+            .execute_batch(
+                "DROP TABLE IF EXISTS keystrokes;
+                 DROP TABLE IF EXISTS records;
+                 DROP TABLE IF EXISTS players;
+                 PRAGMA user_version = 0;",
+            )
             .map_err(ClientError::DropRecordsTableError)
     }
 }
 
+impl RecordStore for Client {
+    fn open(&mut self, app_config_path: PathBuf, db_name: &str) -> Result<(), ClientError> {
+        self.open_connection(app_config_path, db_name)?;
+        self.migrate()
+    }
+
+    fn close(&mut self) -> Result<(), ClientError> {
+        self.close_connection()
+    }
+
+    fn get_records(&self) -> Result<Vec<Record>, ClientError> {
+        Client::get_records(self)
+    }
+
+    fn get_player_records(&self, player_id: i32) -> Result<Vec<Record>, ClientError> {
+        Client::get_player_records(self, player_id)
+    }
+
+    fn get_record_page(&self, query: &RecordQuery) -> Result<Vec<Record>, ClientError> {
+        Client::get_record_page(self, query)
+    }
+
+    fn count_records(&self, player_id: Option<i32>) -> Result<usize, ClientError> {
+        Client::count_records(self, player_id)
+    }
+
+    fn create_record(&mut self, record: &Record) -> Result<i64, ClientError> {
+        Client::create_record(self, record)
+    }
+
+    fn delete_record(&mut self, record_id: i32) -> Result<(), ClientError> {
+        Client::delete_record(self, record_id)
+    }
+
+    fn get_quote_best(
+        &self,
+        quote_id: i32,
+        player_id: Option<i32>,
+    ) -> Result<Option<i64>, ClientError> {
+        Client::get_quote_best(self, quote_id, player_id)
+    }
+
+    fn get_profiles(&self) -> Result<Vec<Profile>, ClientError> {
+        Client::get_profiles(self)
+    }
+
+    fn create_profile(&mut self, name: &str) -> Result<Profile, ClientError> {
+        Client::create_profile(self, name)
+    }
+
+    fn get_player_bests(&self) -> Result<Vec<(String, i64)>, ClientError> {
+        Client::get_player_bests(self)
+    }
+
+    fn create_keystrokes(
+        &mut self,
+        record_id: i64,
+        keystrokes: &[Keystroke],
+    ) -> Result<(), ClientError> {
+        Client::create_keystrokes(self, record_id, keystrokes)
+    }
+
+    fn get_keystrokes(&self, record_id: i32) -> Result<Vec<Keystroke>, ClientError> {
+        Client::get_keystrokes(self, record_id)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::constants::{TEST_APP_PATH, TEST_DB_NAME};
+    use std::fs;
     use std::path::Path;
 
     fn get_test_db_path() -> PathBuf {
@@ -141,11 +511,28 @@ mod tests {
         db_path.to_path_buf()
     }
 
+    fn get_test_record() -> Record {
+        Record {
+            wpm: 35,
+            cpm: 260,
+            date: "2025-01-04 14:07:25".to_string(),
+            raw_wpm: 38,
+            accuracy: 96.5,
+            mode: "words 25".to_string(),
+            consistency: 81.25,
+            burst_wpm: 52,
+            slowest_word_time: 1480,
+            ..Record::default()
+        }
+    }
+
     #[test]
     fn test_client_operations() {
         // OPEN CONNECTION TEST
+        // start from an empty database, a failed run may have left one behind
         let mut client = Client::default();
         let db_name = format!("client_{TEST_DB_NAME}");
+        let _ = fs::remove_file(get_test_db_path().join(&db_name));
         let result = client.open_connection(get_test_db_path(), db_name.as_str());
         assert!(result.is_ok());
         assert!(client.connection.is_some());
@@ -154,13 +541,49 @@ mod tests {
         let result = client.get_connection();
         assert!(result.is_ok());
 
-        // CREATE RECORDS TABLE TEST
-        let result = client.create_records_table();
+        // MIGRATE TEST
+        let result = client.migrate();
+        assert!(result.is_ok());
+        assert_eq!(client.get_schema_version().unwrap(), MIGRATIONS.len());
+        let result = client.migrate();
         assert!(result.is_ok());
 
         // CREATE RECORD TEST
-        let result = client.create_record(35, 260, "2025-01-04 14:07:25".to_string());
+        let result = client.create_record(&get_test_record());
+        assert!(result.is_ok());
+        let record_id = result.unwrap();
+
+        // CREATE KEYSTROKES TEST
+        let keystrokes = vec![
+            Keystroke {
+                timestamp: 180,
+                expected: Some("a".to_string()),
+                typed: 'b',
+                is_correct: false,
+                ..Keystroke::default()
+            },
+            Keystroke {
+                timestamp: 320,
+                expected: None,
+                typed: '\u{8}',
+                is_correct: false,
+                ..Keystroke::default()
+            },
+        ];
+        let result = client.create_keystrokes(record_id, &keystrokes);
+        assert!(result.is_ok());
+
+        // GET KEYSTROKES TEST
+        let result = client.get_keystrokes(record_id as i32);
         assert!(result.is_ok());
+        let saved_keystrokes = result.unwrap();
+        assert_eq!(saved_keystrokes.len(), 2);
+        assert_eq!(saved_keystrokes[0].timestamp, 180);
+        assert_eq!(saved_keystrokes[0].expected, Some("a".to_string()));
+        assert_eq!(saved_keystrokes[0].typed, 'b');
+        assert!(!saved_keystrokes[0].is_correct);
+        assert_eq!(saved_keystrokes[1].expected, None);
+        assert_eq!(saved_keystrokes[1].typed, '\u{8}');
 
         // GET RECORDS TEST
         let records = client.get_records();
@@ -170,6 +593,169 @@ mod tests {
         assert_eq!(records[0].wpm, 35);
         assert_eq!(records[0].cpm, 260);
         assert_eq!(records[0].date, "2025-01-04 14:07:25");
+        assert_eq!(records[0].raw_wpm, 38);
+        assert_eq!(records[0].accuracy, 96.5);
+        assert_eq!(records[0].mode, "words 25");
+        assert_eq!(records[0].consistency, 81.25);
+        assert_eq!(records[0].burst_wpm, 52);
+        assert_eq!(records[0].slowest_word_time, 1480);
+        assert_eq!(records[0].quote_id, None);
+        assert_eq!(records[0].seed, None);
+        assert_eq!(records[0].id as i64, record_id);
+
+        // QUOTE BEST TEST
+        assert_eq!(client.get_quote_best(7, None).unwrap(), None);
+        for wpm in [48, 62, 55] {
+            let record = Record {
+                wpm,
+                mode: "quote short".to_string(),
+                quote_id: Some(7),
+                ..get_test_record()
+            };
+            let result = client.create_record(&record);
+            assert!(result.is_ok());
+        }
+        assert_eq!(client.get_quote_best(7, None).unwrap(), Some(62));
+        assert_eq!(client.get_records().unwrap()[1].quote_id, Some(7));
+
+        // SEED TEST
+        for seed in [20240131, u64::MAX] {
+            let record = Record {
+                seed: Some(seed),
+                ..get_test_record()
+            };
+            let result = client.create_record(&record);
+            assert!(result.is_ok());
+            assert_eq!(
+                client.get_records().unwrap().last().unwrap().seed,
+                Some(seed)
+            );
+        }
+
+        // PARAGRAPH SETTINGS TEST
+        let record = Record {
+            word_list: Some("rust".to_string()),
+            punctuation: true,
+            numbers: true,
+            ..get_test_record()
+        };
+        let result = client.create_record(&record);
+        assert!(result.is_ok());
+        let saved_record = client.get_records().unwrap().last().unwrap().clone();
+        assert_eq!(saved_record.word_list, Some("rust".to_string()));
+        assert!(saved_record.punctuation);
+        assert!(saved_record.numbers);
+
+        // PLAYERS TEST
+        assert!(client.get_profiles().unwrap().is_empty());
+        let ana = client.create_profile("ana").unwrap();
+        let bo = client.create_profile("bo").unwrap();
+        assert!(matches!(
+            client.create_profile("ana"),
+            Err(ClientError::ProfileExistsError(name)) if name == "ana"
+        ));
+        assert_eq!(
+            client.get_profiles().unwrap(),
+            vec![ana.clone(), bo.clone()]
+        );
+        for (player_id, wpm) in [(ana.id, 50), (ana.id, 70), (bo.id, 80)] {
+            let record = Record {
+                wpm,
+                player_id: Some(player_id),
+                ..get_test_record()
+            };
+            let result = client.create_record(&record);
+            assert!(result.is_ok());
+        }
+        let records = client.get_player_records(ana.id).unwrap();
+        assert_eq!(records.len(), 2);
+        assert!(records
+            .iter()
+            .all(|record| record.player_id == Some(ana.id)));
+        assert_eq!(
+            client.get_player_bests().unwrap(),
+            vec![("bo".to_string(), 80), ("ana".to_string(), 70)]
+        );
+
+        // RECORD PAGE TEST
+        let record_count = client.get_records().unwrap().len();
+        assert_eq!(client.count_records(None).unwrap(), record_count);
+        assert_eq!(client.count_records(Some(ana.id)).unwrap(), 2);
+        let query = RecordQuery {
+            column: RecordColumn::Wpm,
+            limit: 2,
+            ..RecordQuery::default()
+        };
+        let page = client.get_record_page(&query).unwrap();
+        assert_eq!(
+            page.iter().map(|record| record.wpm).collect::<Vec<i64>>(),
+            vec![80, 70]
+        );
+        let query = RecordQuery { offset: 1, ..query };
+        assert_eq!(client.get_record_page(&query).unwrap()[0].wpm, 70);
+        let query = RecordQuery {
+            player_id: Some(ana.id),
+            ascending: true,
+            limit: 10,
+            offset: 0,
+            ..query
+        };
+        let page = client.get_record_page(&query).unwrap();
+        assert_eq!(
+            page.iter().map(|record| record.wpm).collect::<Vec<i64>>(),
+            vec![50, 70]
+        );
+        // ties keep the direction of the sort
+        let query = RecordQuery {
+            column: RecordColumn::Date,
+            player_id: None,
+            ascending: false,
+            ..query
+        };
+        let page = client.get_record_page(&query).unwrap();
+        assert_eq!(page.len(), record_count);
+        assert!(page.windows(2).all(|pair| pair[0].id > pair[1].id));
+        let query = RecordQuery {
+            column: RecordColumn::Mode,
+            ascending: true,
+            ..query
+        };
+        for mode in ["time 120", "words 10", "time 15"] {
+            let record = Record {
+                mode: mode.to_string(),
+                ..get_test_record()
+            };
+            let result = client.create_record(&record);
+            assert!(result.is_ok());
+        }
+        let page = client.get_record_page(&query).unwrap();
+        let modes = page
+            .iter()
+            .map(|record| record.mode.as_str())
+            .filter(|mode| ["time 120", "words 10", "time 15"].contains(mode))
+            .collect::<Vec<&str>>();
+        assert_eq!(modes, vec!["time 15", "time 120", "words 10"]);
+
+        // PLAYER QUOTE BEST TEST
+        let record = Record {
+            wpm: 90,
+            quote_id: Some(7),
+            player_id: Some(bo.id),
+            ..get_test_record()
+        };
+        let result = client.create_record(&record);
+        assert!(result.is_ok());
+        assert_eq!(client.get_quote_best(7, Some(bo.id)).unwrap(), Some(90));
+        assert_eq!(client.get_quote_best(7, Some(ana.id)).unwrap(), None);
+        // guests only compare against records without a player
+        assert_eq!(client.get_quote_best(7, None).unwrap(), Some(62));
+
+        // DELETE RECORD TEST
+        let record_count = client.get_records().unwrap().len();
+        let result = client.delete_record(record_id as i32);
+        assert!(result.is_ok());
+        assert_eq!(client.get_records().unwrap().len(), record_count - 1);
+        assert!(client.get_keystrokes(record_id as i32).unwrap().is_empty());
 
         // DROP RECORDS TABLE TEST
         let result = client.drop_records_table();
@@ -179,6 +765,8 @@ mod tests {
         let result = client.close_connection();
         assert!(result.is_ok());
         assert!(client.connection.is_none());
+        let result = fs::remove_file(get_test_db_path().join(&db_name));
+        assert!(result.is_ok());
     }
 
     #[test]
@@ -197,12 +785,75 @@ mod tests {
     }
 
     #[test]
-    fn test_create_records_table_error() {
+    fn test_migrate_error() {
         let client = Client::default();
-        let result = client.create_records_table();
+        let result = client.migrate();
+        assert!(result.is_err());
+        let result = client.get_schema_version();
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_migrate_legacy_database() {
+        // migrate a copy so the fixture keeps its original schema
+        let db_name = format!("legacy_copy_{TEST_DB_NAME}");
+        let db_path = get_test_db_path().join(&db_name);
+        let result = fs::copy(
+            get_test_db_path().join(format!("legacy_{TEST_DB_NAME}")),
+            &db_path,
+        );
+        assert!(result.is_ok());
+
+        let mut client = Client::default();
+        let result = client.open_connection(get_test_db_path(), db_name.as_str());
+        assert!(result.is_ok());
+        assert_eq!(client.get_schema_version().unwrap(), 0);
+
+        let result = client.migrate();
+        assert!(result.is_ok());
+        assert_eq!(client.get_schema_version().unwrap(), MIGRATIONS.len());
+
+        // existing records survive and get the column defaults
+        let records = client.get_records().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].wpm, 35);
+        assert_eq!(records[0].cpm, 260);
+        assert_eq!(records[0].date, "2025-01-04 14:07:25");
+        assert_eq!(records[0].raw_wpm, 0);
+        assert_eq!(records[0].mode, "time 60");
+        assert_eq!(records[0].consistency, 0.0);
+        assert_eq!(records[0].quote_id, None);
+        assert_eq!(records[0].seed, None);
+        assert_eq!(records[0].player_id, None);
+        assert_eq!(records[0].word_list, None);
+        assert!(!records[0].punctuation);
+        assert_eq!(records[1].wpm, 41);
+
+        let result = client.create_record(&get_test_record());
+        assert!(result.is_ok());
+        assert_eq!(client.get_records().unwrap().len(), 3);
+
+        let result = client.close_connection();
+        assert!(result.is_ok());
+        let result = fs::remove_file(db_path);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_migrate_newer_database() {
+        let mut client = Client::default();
+        let db_name = format!("future_{TEST_DB_NAME}");
+        let result = client.open_connection(get_test_db_path(), db_name.as_str());
+        assert!(result.is_ok());
+
+        let result = client.migrate();
+        assert!(matches!(
+            result,
+            Err(ClientError::UnsupportedSchemaVersionError(99, _))
+        ));
+        assert_eq!(client.get_schema_version().unwrap(), 99);
+    }
+
     #[test]
     fn test_get_records_error() {
         let client = Client::default();
@@ -213,7 +864,23 @@ mod tests {
     #[test]
     fn test_create_record_error() {
         let client = Client::default();
-        let result = client.create_record(35, 260, "2025-01-04 14:07:25".to_string());
+        let result = client.create_record(&get_test_record());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_keystrokes_error() {
+        let client = Client::default();
+        let result = client.create_keystrokes(1, &[Keystroke::default()]);
+        assert!(result.is_err());
+        let result = client.get_keystrokes(1);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_get_quote_best_error() {
+        let client = Client::default();
+        let result = client.get_quote_best(1, None);
         assert!(result.is_err());
     }
 
@@ -0,0 +1,113 @@
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+
+use chrono::{DateTime, Local, NaiveDate};
+
+/// Where the timers of a test read the time from.
+pub trait Clock: Debug + Send + Sync {
+    /// Milliseconds since the unix epoch.
+    fn now_millis(&self) -> i64;
+}
+
+/// The wall clock.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_millis(&self) -> i64 {
+        Local::now().timestamp_millis()
+    }
+}
+
+/// A clock that only moves when it's told to, clones share the same time.
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    millis: Arc<AtomicI64>,
+}
+
+impl ManualClock {
+    /// Starts the clock at `millis`. Timers treat 0 as not started, so start past it.
+    pub fn new(millis: i64) -> Self {
+        Self {
+            millis: Arc::new(AtomicI64::new(millis)),
+        }
+    }
+
+    pub fn advance(&self, millis: i64) {
+        self.millis.fetch_add(millis, Ordering::SeqCst);
+    }
+
+    pub fn set(&self, millis: i64) {
+        self.millis.store(millis, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_millis(&self) -> i64 {
+        self.millis.load(Ordering::SeqCst)
+    }
+}
+
+/// The clock shared by the state and everything reading its timers, the system clock
+/// unless another one is injected.
+#[derive(Debug, Clone)]
+pub struct SharedClock(Arc<dyn Clock>);
+
+impl SharedClock {
+    pub fn new(clock: impl Clock + 'static) -> Self {
+        Self(Arc::new(clock))
+    }
+
+    pub fn now_millis(&self) -> i64 {
+        self.0.now_millis()
+    }
+
+    /// The local date at the current time, e.g. to pick the daily challenge.
+    pub fn today(&self) -> NaiveDate {
+        DateTime::from_timestamp_millis(self.now_millis())
+            .unwrap_or_default()
+            .with_timezone(&Local)
+            .date_naive()
+    }
+}
+
+impl Default for SharedClock {
+    fn default() -> Self {
+        SharedClock::new(SystemClock)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_manual_clock() {
+        let clock = ManualClock::new(1000);
+        let shared_clock = SharedClock::new(clock.clone());
+        assert_eq!(shared_clock.now_millis(), 1000);
+        clock.advance(250);
+        assert_eq!(shared_clock.now_millis(), 1250);
+        clock.set(60_000);
+        assert_eq!(shared_clock.now_millis(), 60_000);
+
+        let noon = Local.with_ymd_and_hms(2024, 1, 31, 12, 0, 0).unwrap();
+        clock.set(noon.timestamp_millis());
+        assert_eq!(shared_clock.today(), noon.date_naive());
+        clock.advance(24 * 60 * 60 * 1000);
+        assert_eq!(
+            shared_clock.today(),
+            NaiveDate::from_ymd_opt(2024, 2, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_system_clock() {
+        let before = Local::now().timestamp_millis();
+        let now = SharedClock::default().now_millis();
+        assert!(now >= before);
+        assert!(now - before < 1000);
+    }
+}
@@ -0,0 +1,361 @@
+use std::{fmt::Display, str::FromStr};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::style::{Color, Modifier};
+use serde::{Deserialize, Deserializer};
+use thiserror::Error;
+
+use crate::constants::{Mode, Page, COUNTDOWN_DURATION, MAX_COUNTDOWN};
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("Could not parse config file: {0}")]
+    ParseConfigError(toml::de::Error),
+    #[error("Invalid countdown {0}, expected 1 to {MAX_COUNTDOWN} seconds")]
+    InvalidCountdownError(i64),
+}
+
+/// User settings read from `config.toml`, every missing key falls back to its default.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub mode: Mode,
+    /// Path of a newline separated word list, relative to the config folder.
+    pub word_list: Option<String>,
+    /// Mix punctuation and capitals into generated paragraphs.
+    pub punctuation: bool,
+    /// Mix numbers into generated paragraphs.
+    pub numbers: bool,
+    /// Fill in the indentation after a line break in code mode.
+    pub skip_indentation: bool,
+    /// Seconds counted down before a test, 1 to `MAX_COUNTDOWN`.
+    pub countdown: i64,
+    pub caret: CaretStyle,
+    pub theme: Theme,
+    pub key_bindings: KeyBindings,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            mode: Mode::default(),
+            word_list: None,
+            punctuation: false,
+            numbers: false,
+            skip_indentation: true,
+            countdown: COUNTDOWN_DURATION,
+            caret: CaretStyle::default(),
+            theme: Theme::default(),
+            key_bindings: KeyBindings::default(),
+        }
+    }
+}
+
+impl Config {
+    pub fn parse(content: &str) -> Result<Self, ConfigError> {
+        let config: Config = toml::from_str(content).map_err(ConfigError::ParseConfigError)?;
+        if !(1..=MAX_COUNTDOWN).contains(&config.countdown) {
+            return Err(ConfigError::InvalidCountdownError(config.countdown));
+        }
+        Ok(config)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaretStyle {
+    #[default]
+    Underline,
+    Block,
+}
+
+impl CaretStyle {
+    pub fn get_modifier(&self) -> Modifier {
+        match self {
+            CaretStyle::Underline => Modifier::UNDERLINED,
+            CaretStyle::Block => Modifier::REVERSED,
+        }
+    }
+}
+
+/// Colors of the paragraph, accepts names like "green", hex codes and ansi indexes.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    pub correct: Color,
+    pub incorrect: Color,
+    pub extra: Color,
+    pub missed: Color,
+    pub untyped: Color,
+    /// Background of the ghost's caret.
+    pub ghost: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            correct: Color::Green,
+            incorrect: Color::Red,
+            extra: Color::Rgb(120, 20, 20),
+            missed: Color::Red,
+            untyped: Color::Rgb(21, 21, 21),
+            ghost: Color::Cyan,
+        }
+    }
+}
+
+/// Extra keys for the built-in controls, e.g. `back = "ctrl+q"`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeyBindings {
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub back: KeyBinding,
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub select: KeyBinding,
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub delete_word: KeyBinding,
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub up: KeyBinding,
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub down: KeyBinding,
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub left: KeyBinding,
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub right: KeyBinding,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            back: KeyBinding::new(KeyCode::Esc, KeyModifiers::NONE),
+            select: KeyBinding::new(KeyCode::Enter, KeyModifiers::NONE),
+            delete_word: KeyBinding::new(KeyCode::Char('w'), KeyModifiers::CONTROL),
+            up: KeyBinding::new(KeyCode::Up, KeyModifiers::NONE),
+            down: KeyBinding::new(KeyCode::Down, KeyModifiers::NONE),
+            left: KeyBinding::new(KeyCode::Left, KeyModifiers::NONE),
+            right: KeyBinding::new(KeyCode::Right, KeyModifiers::NONE),
+        }
+    }
+}
+
+impl KeyBindings {
+    /// Maps a bound key to the key the controller handles by default, so the default
+    /// keys keep working. Plain characters are left alone while typing a test or a
+    /// profile name.
+    pub fn resolve(&self, key: KeyEvent, page: &Page) -> KeyEvent {
+        let is_text = matches!(key.code, KeyCode::Char(_))
+            && !key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+        if matches!(page, Page::Game | Page::Profiles) && is_text {
+            return key;
+        }
+
+        let defaults = KeyBindings::default();
+        [
+            (self.back, defaults.back),
+            (self.select, defaults.select),
+            (self.delete_word, defaults.delete_word),
+            (self.up, defaults.up),
+            (self.down, defaults.down),
+            (self.left, defaults.left),
+            (self.right, defaults.right),
+        ]
+        .iter()
+        .find(|(binding, _)| binding.matches(&key))
+        .map(|(_, default)| KeyEvent::new(default.code, default.modifiers))
+        .unwrap_or(key)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
+
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        // shift is part of the character itself for letters and symbols
+        let modifiers = match key.code {
+            KeyCode::Char(_) => key.modifiers - KeyModifiers::SHIFT,
+            _ => key.modifiers,
+        };
+        self.code == key.code && self.modifiers == modifiers
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = String;
+
+    /// Parses keys like "esc", "ctrl+w" or "alt+left".
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid_key = || format!("invalid key binding \"{value}\"");
+        let lowercase = value.trim().to_lowercase();
+        let mut parts = lowercase.split('+').collect::<Vec<&str>>();
+        let key = parts.pop().ok_or_else(invalid_key)?;
+
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in parts {
+            modifiers |= match modifier {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(invalid_key()),
+            };
+        }
+
+        let code = match key {
+            "esc" | "escape" => KeyCode::Esc,
+            "enter" | "return" => KeyCode::Enter,
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            _ => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(character), None) => KeyCode::Char(character),
+                    _ => return Err(invalid_key()),
+                }
+            }
+        };
+        Ok(KeyBinding::new(code, modifiers))
+    }
+}
+
+fn deserialize_from_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    let value = String::deserialize(deserializer)?;
+    value.parse::<T>().map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_empty_config() {
+        let config = Config::parse("");
+        assert!(config.is_ok());
+        assert_eq!(config.unwrap(), Config::default());
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let content = r##"
+            mode = "words 25"
+            word_list = "words.txt"
+            punctuation = true
+            skip_indentation = false
+            countdown = 5
+            caret = "block"
+
+            [theme]
+            correct = "blue"
+            extra = "#ff0000"
+
+            [key_bindings]
+            back = "ctrl+q"
+        "##;
+        let config = Config::parse(content).unwrap();
+        assert_eq!(config.mode, Mode::Words(25));
+        assert_eq!(config.word_list, Some("words.txt".to_string()));
+        assert!(config.punctuation);
+        assert!(!config.numbers);
+        assert!(!config.skip_indentation);
+        assert_eq!(config.countdown, 5);
+        assert_eq!(config.caret, CaretStyle::Block);
+        assert_eq!(config.theme.correct, Color::Blue);
+        assert_eq!(config.theme.extra, Color::Rgb(255, 0, 0));
+        // keys missing from a table keep their defaults
+        assert_eq!(config.theme.incorrect, Color::Red);
+        assert_eq!(
+            config.key_bindings.back,
+            KeyBinding::new(KeyCode::Char('q'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(config.key_bindings.select, KeyBindings::default().select);
+    }
+
+    #[test]
+    fn test_parse_invalid_config() {
+        assert!(Config::parse("mode = \"time\"").is_err());
+        assert!(Config::parse("countdown = \"three\"").is_err());
+        assert!(Config::parse("unknown = 1").is_err());
+        assert!(Config::parse("[theme]\ncorrect = \"not a color\"").is_err());
+        assert!(Config::parse("[key_bindings]\nback = \"hyper+q\"").is_err());
+        assert!(Config::parse("caret = \"bar\"").is_err());
+
+        for countdown in [0, -3, MAX_COUNTDOWN + 1] {
+            let result = Config::parse(&format!("countdown = {countdown}"));
+            assert!(matches!(
+                result,
+                Err(ConfigError::InvalidCountdownError(value)) if value == countdown
+            ));
+        }
+        assert!(Config::parse(&format!("countdown = {MAX_COUNTDOWN}")).is_ok());
+    }
+
+    #[test]
+    fn test_key_binding_from_str() {
+        assert_eq!(
+            "esc".parse::<KeyBinding>(),
+            Ok(KeyBinding::new(KeyCode::Esc, KeyModifiers::NONE))
+        );
+        assert_eq!(
+            "Ctrl+Alt+Left".parse::<KeyBinding>(),
+            Ok(KeyBinding::new(
+                KeyCode::Left,
+                KeyModifiers::CONTROL | KeyModifiers::ALT
+            ))
+        );
+        assert_eq!(
+            "k".parse::<KeyBinding>(),
+            Ok(KeyBinding::new(KeyCode::Char('k'), KeyModifiers::NONE))
+        );
+        assert!("".parse::<KeyBinding>().is_err());
+        assert!("ctrl+".parse::<KeyBinding>().is_err());
+        assert!("kk".parse::<KeyBinding>().is_err());
+    }
+
+    #[test]
+    fn test_resolve_key_bindings() {
+        let key_bindings = KeyBindings {
+            back: "ctrl+q".parse().unwrap(),
+            down: "j".parse().unwrap(),
+            ..KeyBindings::default()
+        };
+
+        let key = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL);
+        let resolved = key_bindings.resolve(key, &Page::Game);
+        assert_eq!(resolved.code, KeyCode::Esc);
+
+        let key = KeyEvent::from(KeyCode::Char('j'));
+        assert_eq!(key_bindings.resolve(key, &Page::Menu).code, KeyCode::Down);
+        // plain characters are typed during a test
+        assert_eq!(key_bindings.resolve(key, &Page::Game), key);
+        // and in profile names
+        assert_eq!(key_bindings.resolve(key, &Page::Profiles), key);
+
+        let key = KeyEvent::from(KeyCode::Esc);
+        assert_eq!(key_bindings.resolve(key, &Page::Menu), key);
+    }
+
+    #[test]
+    fn test_caret_style_modifier() {
+        assert_eq!(CaretStyle::Underline.get_modifier(), Modifier::UNDERLINED);
+        assert_eq!(CaretStyle::Block.get_modifier(), Modifier::REVERSED);
+    }
+}
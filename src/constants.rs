@@ -1,11 +1,28 @@
 pub use std::fmt;
+use std::str::FromStr;
+
+use serde::Deserialize;
 
 pub const APP_PATH: &str = "typefast";
 pub const DB_NAME: &str = "typefast.db";
+pub const CONFIG_NAME: &str = "config.toml";
+pub const WORD_LISTS_PATH: &str = "wordlists";
+// written to the config folder when the app panics
+pub const CRASH_REPORT_NAME: &str = "crash.log";
+pub const DEFAULT_WORD_LIST_NAME: &str = "default";
 pub const TEST_APP_PATH: &str = "__test__";
 pub const TEST_DB_NAME: &str = "mock.db";
-pub const GAME_DURATION: i64 = 60;
 pub const COUNTDOWN_DURATION: i64 = 3;
+// longest countdown the config accepts
+pub const MAX_COUNTDOWN: i64 = 60;
+pub const CHARS_PER_WORD: f64 = 5.0;
+pub const VIEWPORT_LINES: usize = 3;
+// how long the event loop waits for a key before redrawing
+pub const EVENT_POLL_MILLIS: u64 = 16;
+// seconds the burst speed is averaged over
+pub const BURST_WINDOW: usize = 3;
+pub const BACKSPACE_CHAR: char = '\u{8}';
+pub const DELETE_WORD_CHAR: char = '\u{17}';
 pub const TEST_WORDS: [&str; 21] = [
     "plant",
     "planta",
@@ -30,7 +47,71 @@ pub const TEST_WORDS: [&str; 21] = [
     "more",
 ];
 pub const WORD_LIST: &str = include_str!("../assets/word_list.txt");
-pub const MENU_ITEMS: [&str; 2] = ["Start", "Records"];
+pub const QUOTES: &str = include_str!("../assets/quotes.toml");
+pub const SNIPPETS: &str = include_str!("../assets/snippets.toml");
+// longest quotes in characters for the short and medium groups
+pub const SHORT_QUOTE_LENGTH: usize = 100;
+pub const MEDIUM_QUOTE_LENGTH: usize = 250;
+pub const MENU_ITEMS: [&str; 9] = [
+    "Start",
+    "Mode",
+    "Word list",
+    "Punctuation",
+    "Numbers",
+    "Ghost",
+    "Hot seat",
+    "Profile",
+    "Records",
+];
+pub const MODES: [Mode; 16] = [
+    Mode::Time(15),
+    Mode::Time(30),
+    Mode::Time(60),
+    Mode::Time(120),
+    Mode::Words(10),
+    Mode::Words(25),
+    Mode::Words(50),
+    Mode::Words(100),
+    Mode::Quote(QuoteLength::Short),
+    Mode::Quote(QuoteLength::Medium),
+    Mode::Quote(QuoteLength::Long),
+    Mode::Code(Language::Rust),
+    Mode::Code(Language::Python),
+    Mode::Code(Language::JavaScript),
+    Mode::Code(Language::Shell),
+    Mode::Daily,
+];
+// chance of a generated word to become a number or to get punctuation
+pub const NUMBER_RATE: f64 = 0.1;
+pub const PUNCTUATION_RATE: f64 = 0.3;
+// words of the daily challenge, typed from the bundled list
+pub const DAILY_WORD_COUNT: usize = 50;
+// enough words for a 240 wpm run so timed tests don't run out of text
+pub const WORDS_PER_SECOND: usize = 4;
+// longest test `--time` and `--words` accept, an hour and a paragraph an hour would fill
+pub const MAX_TEST_SECONDS: i64 = 3600;
+pub const MAX_TEST_WORDS: u64 = 14400;
+pub const DEFAULT_RACE_PORT: u16 = 7878;
+// how long the race server waits on a player that doesn't read before dropping it
+pub const RACE_WRITE_TIMEOUT_MILLIS: u64 = 1000;
+// cells of the race panel beside the paragraph and of the progress bars in it
+pub const RACE_PANEL_WIDTH: u16 = 24;
+pub const RACE_BAR_WIDTH: usize = 12;
+// name of players that don't pass `--name` and have no user name
+pub const DEFAULT_PLAYER_NAME: &str = "player";
+// players taking turns in a hot seat tournament
+pub const MAX_HOT_SEAT_PLAYERS: usize = 8;
+// how records without a profile show up
+pub const GUEST_NAME: &str = "guest";
+// rows of the records table fetched and shown at a time
+pub const RECORDS_PAGE_SIZE: usize = 10;
+pub const RECORD_COLUMNS: [RecordColumn; 5] = [
+    RecordColumn::Date,
+    RecordColumn::Mode,
+    RecordColumn::Wpm,
+    RecordColumn::Accuracy,
+    RecordColumn::Consistency,
+];
 
 #[derive(Debug, Default, PartialEq, Copy, Clone)]
 pub enum Page {
@@ -40,6 +121,8 @@ pub enum Page {
     CountDown,
     Records,
     GameResult,
+    Profiles,
+    Leaderboard,
 }
 
 impl fmt::Display for Page {
@@ -50,6 +133,175 @@ impl fmt::Display for Page {
             Page::CountDown => write!(f, "CountDown"),
             Page::Records => write!(f, "Records"),
             Page::GameResult => write!(f, "GameResult"),
+            Page::Profiles => write!(f, "Profiles"),
+            Page::Leaderboard => write!(f, "Leaderboard"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Mode {
+    /// Type for the given number of seconds.
+    Time(i64),
+    /// Type the given number of words, the test ends with the last one.
+    Words(usize),
+    /// Type a quote of the bundled corpus, the test ends with its last word.
+    Quote(QuoteLength),
+    /// Type a source snippet of the language, line breaks are typed with enter.
+    Code(Language),
+    /// Type the words of the day, the seed comes from the local date so everyone
+    /// gets the same text.
+    Daily,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum QuoteLength {
+    Short,
+    Medium,
+    Long,
+}
+
+impl QuoteLength {
+    pub fn from_text_length(length: usize) -> Self {
+        if length <= SHORT_QUOTE_LENGTH {
+            QuoteLength::Short
+        } else if length <= MEDIUM_QUOTE_LENGTH {
+            QuoteLength::Medium
+        } else {
+            QuoteLength::Long
+        }
+    }
+}
+
+impl fmt::Display for QuoteLength {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuoteLength::Short => write!(f, "short"),
+            QuoteLength::Medium => write!(f, "medium"),
+            QuoteLength::Long => write!(f, "long"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Copy, Clone, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    Rust,
+    Python,
+    JavaScript,
+    Shell,
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Language::Rust => write!(f, "rust"),
+            Language::Python => write!(f, "python"),
+            Language::JavaScript => write!(f, "javascript"),
+            Language::Shell => write!(f, "shell"),
+        }
+    }
+}
+
+impl Default for Mode {
+    fn default() -> Self {
+        Mode::Time(60)
+    }
+}
+
+impl Mode {
+    /// Returns how many words to pick for the paragraph, quotes and snippets bring their
+    /// own text.
+    pub fn get_paragraph_length(&self) -> usize {
+        match self {
+            Mode::Time(seconds) => *seconds as usize * WORDS_PER_SECOND,
+            Mode::Words(word_count) => *word_count,
+            Mode::Daily => DAILY_WORD_COUNT,
+            Mode::Quote(_) | Mode::Code(_) => 0,
+        }
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mode::Time(seconds) => write!(f, "time {}", seconds),
+            Mode::Words(word_count) => write!(f, "words {}", word_count),
+            Mode::Quote(length) => write!(f, "quote {}", length),
+            Mode::Code(language) => write!(f, "code {}", language),
+            Mode::Daily => write!(f, "daily"),
+        }
+    }
+}
+
+impl FromStr for Mode {
+    type Err = String;
+
+    /// Parses the `Display` form of a mode, e.g. "time 60", "words 25" or "quote short".
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid_mode = || {
+            format!(
+                "invalid mode \"{value}\", expected \"time <seconds>\", \"words <count>\", \
+                 \"quote <short|medium|long>\", \"code <rust|python|javascript|shell>\" or \"daily\""
+            )
+        };
+        if value.trim() == "daily" {
+            return Ok(Mode::Daily);
+        }
+        let (kind, length) = value.trim().split_once(' ').ok_or_else(invalid_mode)?;
+        match kind {
+            "time" => length
+                .trim()
+                .parse::<i64>()
+                .ok()
+                .filter(|seconds| *seconds > 0)
+                .map(Mode::Time)
+                .ok_or_else(invalid_mode),
+            "words" => length
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|word_count| *word_count > 0)
+                .map(Mode::Words)
+                .ok_or_else(invalid_mode),
+            "quote" => match length.trim() {
+                "short" => Ok(Mode::Quote(QuoteLength::Short)),
+                "medium" => Ok(Mode::Quote(QuoteLength::Medium)),
+                "long" => Ok(Mode::Quote(QuoteLength::Long)),
+                _ => Err(invalid_mode()),
+            },
+            "code" => match length.trim() {
+                "rust" => Ok(Mode::Code(Language::Rust)),
+                "python" => Ok(Mode::Code(Language::Python)),
+                "javascript" => Ok(Mode::Code(Language::JavaScript)),
+                "shell" => Ok(Mode::Code(Language::Shell)),
+                _ => Err(invalid_mode()),
+            },
+            _ => Err(invalid_mode()),
+        }
+    }
+}
+
+/// A column of the records table, the records can be sorted by any of them.
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
+pub enum RecordColumn {
+    #[default]
+    Date,
+    Mode,
+    Wpm,
+    Accuracy,
+    Consistency,
+}
+
+impl fmt::Display for RecordColumn {
+    /// Writes the name of the column in the records table of the database.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordColumn::Date => write!(f, "date"),
+            RecordColumn::Mode => write!(f, "mode"),
+            RecordColumn::Wpm => write!(f, "wpm"),
+            RecordColumn::Accuracy => write!(f, "accuracy"),
+            RecordColumn::Consistency => write!(f, "consistency"),
         }
     }
 }
@@ -60,10 +312,28 @@ pub enum Action {
     Empty,
     Exit,
     CharInput(char),
+    DeleteChar,
+    DeleteWord,
     ChangePage(Page),
     GetRecords,
     PostRecord,
-    MenuAction,
+    MenuAction(i32),
+    ChangeMode(i32),
+    ChangeWordList(i32),
+    TogglePunctuation,
+    ToggleNumbers,
+    ToggleGhost,
+    StartRace,
+    ProfileAction(i32),
+    ProfileNameInput(char),
+    DeleteProfileNameChar,
+    PickProfile,
+    ChangeHotSeat(i32),
+    StartHotSeat,
+    EndHotSeat,
+    RecordAction(i32),
+    SortRecords(i32),
+    ReverseRecords,
 }
 
 #[cfg(test)]
@@ -77,6 +347,8 @@ mod tests {
         assert_eq!(Page::CountDown.to_string(), "CountDown");
         assert_eq!(Page::Records.to_string(), "Records");
         assert_eq!(Page::GameResult.to_string(), "GameResult");
+        assert_eq!(Page::Profiles.to_string(), "Profiles");
+        assert_eq!(Page::Leaderboard.to_string(), "Leaderboard");
     }
 
     #[test]
@@ -84,28 +356,109 @@ mod tests {
         assert_eq!(Page::default(), Page::Menu);
     }
 
+    #[test]
+    fn test_quote_length() {
+        assert_eq!(QuoteLength::from_text_length(16), QuoteLength::Short);
+        assert_eq!(QuoteLength::from_text_length(100), QuoteLength::Short);
+        assert_eq!(QuoteLength::from_text_length(101), QuoteLength::Medium);
+        assert_eq!(QuoteLength::from_text_length(250), QuoteLength::Medium);
+        assert_eq!(QuoteLength::from_text_length(251), QuoteLength::Long);
+    }
+
+    #[test]
+    fn test_mode_from_str() {
+        assert_eq!("time 30".parse::<Mode>(), Ok(Mode::Time(30)));
+        assert_eq!("words 25".parse::<Mode>(), Ok(Mode::Words(25)));
+        assert_eq!(
+            Mode::Words(50).to_string().parse::<Mode>(),
+            Ok(Mode::Words(50))
+        );
+        assert!("time".parse::<Mode>().is_err());
+        assert!("time 0".parse::<Mode>().is_err());
+        assert!("words -5".parse::<Mode>().is_err());
+        assert_eq!(
+            "quote medium".parse::<Mode>(),
+            Ok(Mode::Quote(QuoteLength::Medium))
+        );
+        assert!("quote 10".parse::<Mode>().is_err());
+        assert!("sentence short".parse::<Mode>().is_err());
+        assert_eq!(
+            "code javascript".parse::<Mode>(),
+            Ok(Mode::Code(Language::JavaScript))
+        );
+        assert!("code cobol".parse::<Mode>().is_err());
+        assert_eq!(" daily ".parse::<Mode>(), Ok(Mode::Daily));
+        assert_eq!(
+            "sentence short".parse::<Mode>(),
+            Err("invalid mode \"sentence short\", expected \"time <seconds>\", \"words <count>\", \
+                 \"quote <short|medium|long>\", \"code <rust|python|javascript|shell>\" or \"daily\""
+                .to_string())
+        );
+    }
+
     #[test]
     fn test_action() {
         assert_eq!(Action::Init, Action::Init);
         assert_eq!(Action::Empty, Action::Empty);
         assert_eq!(Action::Exit, Action::Exit);
         assert_eq!(Action::CharInput('a'), Action::CharInput('a'));
+        assert_eq!(Action::DeleteChar, Action::DeleteChar);
+        assert_eq!(Action::DeleteWord, Action::DeleteWord);
         assert_eq!(
             Action::ChangePage(Page::Game),
             Action::ChangePage(Page::Game)
         );
         assert_eq!(Action::GetRecords, Action::GetRecords);
         assert_eq!(Action::PostRecord, Action::PostRecord);
-        assert_eq!(Action::MenuAction, Action::MenuAction);
+        assert_eq!(Action::MenuAction(1), Action::MenuAction(1));
+        assert_eq!(Action::ChangeMode(-1), Action::ChangeMode(-1));
+        assert_eq!(Action::ChangeWordList(1), Action::ChangeWordList(1));
+        assert_eq!(Action::TogglePunctuation, Action::TogglePunctuation);
+        assert_eq!(Action::ToggleNumbers, Action::ToggleNumbers);
+        assert_eq!(Action::ToggleGhost, Action::ToggleGhost);
     }
 
     #[test]
     fn test_constants() {
         assert_eq!(APP_PATH, "typefast");
         assert_eq!(DB_NAME, "typefast.db");
-        assert_eq!(GAME_DURATION, 60);
+        assert_eq!(CONFIG_NAME, "config.toml");
+        assert_eq!(WORD_LISTS_PATH, "wordlists");
+        assert_eq!(CRASH_REPORT_NAME, "crash.log");
         assert_eq!(COUNTDOWN_DURATION, 3);
+        assert_eq!(CHARS_PER_WORD, 5.0);
+        assert_eq!(BURST_WINDOW, 3);
+        assert_eq!(VIEWPORT_LINES, 3);
         assert_eq!(TEST_WORDS.len(), 21);
-        assert_eq!(MENU_ITEMS, ["Start", "Records"]);
+        assert_eq!(
+            MENU_ITEMS,
+            [
+                "Start",
+                "Mode",
+                "Word list",
+                "Punctuation",
+                "Numbers",
+                "Ghost",
+                "Hot seat",
+                "Profile",
+                "Records"
+            ]
+        );
+        assert_eq!(MODES.len(), 16);
+    }
+
+    #[test]
+    fn test_mode() {
+        assert_eq!(Mode::default(), Mode::Time(60));
+        assert_eq!(Mode::Time(30).to_string(), "time 30");
+        assert_eq!(Mode::Words(25).to_string(), "words 25");
+        assert_eq!(Mode::Time(30).get_paragraph_length(), 120);
+        assert_eq!(Mode::Words(25).get_paragraph_length(), 25);
+        assert_eq!(Mode::Quote(QuoteLength::Long).to_string(), "quote long");
+        assert_eq!(Mode::Quote(QuoteLength::Short).get_paragraph_length(), 0);
+        assert_eq!(Mode::Code(Language::Shell).to_string(), "code shell");
+        assert_eq!(Mode::Code(Language::Rust).get_paragraph_length(), 0);
+        assert_eq!(Mode::Daily.to_string(), "daily");
+        assert_eq!(Mode::Daily.get_paragraph_length(), DAILY_WORD_COUNT);
     }
 }
@@ -1,23 +1,46 @@
-use std::path::PathBuf;
+use std::env;
+use std::net::ToSocketAddrs;
+use std::path::{Path, PathBuf};
 
-use chrono::Local;
-use crossterm::event::{self, KeyCode, KeyEventKind};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::prelude::{Backend, Terminal};
 use thiserror::Error;
 
+use crate::cli::{Cli, Command};
 use crate::client::{Client, ClientError};
-use crate::constants::{Action, Page, COUNTDOWN_DURATION, DB_NAME, GAME_DURATION};
-use crate::filesystem::{create_config_folder, get_app_config_path, get_words, FileSystemError};
+use crate::clock::SharedClock;
+use crate::config::{Config, ConfigError};
+use crate::constants::{
+    Action, Mode, Page, RecordColumn, BACKSPACE_CHAR, CONFIG_NAME, DEFAULT_PLAYER_NAME,
+    DEFAULT_WORD_LIST_NAME, DELETE_WORD_CHAR, EVENT_POLL_MILLIS, GUEST_NAME, MAX_HOT_SEAT_PLAYERS,
+    MENU_ITEMS, MODES, QUOTES, RECORDS_PAGE_SIZE, RECORD_COLUMNS, SNIPPETS, WORD_LIST,
+    WORD_LISTS_PATH,
+};
+use crate::events::EventSource;
+use crate::filesystem::{
+    create_config_folder, file_exists, get_app_config_path, get_quote, get_quotes, get_snippet,
+    get_snippets, get_word_list_paths, get_words, load_word_list, read_file, FileSystemError,
+};
+use crate::ghost::{Ghost, GhostTarget};
+use crate::hot_seat::{HotSeat, HotSeatResult};
+use crate::race::{ClientMessage, Progress, RaceClient, RaceError, RaceServer, ServerMessage};
+use crate::record::Record;
 use crate::state::State;
-use crate::util::{calculate_char_speed, calculate_word_speed, get_current_datetime};
+use crate::store::{MemoryStore, RecordQuery, RecordStore};
+use crate::util::{
+    add_numbers, add_punctuation, calculate_word_speed, get_current_datetime, get_daily_seed,
+    get_rng,
+};
 use crate::view::{View, ViewError};
 
 type DynamicError = Box<dyn std::error::Error>;
 
-#[derive(Default)]
 pub struct Controller {
     pub state: State,
-    client: Client,
+    store: Box<dyn RecordStore>,
+    race: Option<RaceClient>,
+    /// The progress last sent to the race, sent again only when it changes.
+    race_progress: Option<Progress>,
 }
 
 #[derive(Error, Debug)]
@@ -27,19 +50,52 @@ pub enum ControllerError {
     #[error("{0}")]
     FileSystemError(#[from] FileSystemError),
     #[error("{0}")]
+    ConfigError(#[from] ConfigError),
+    #[error("{0}")]
     ViewError(#[from] ViewError),
+    #[error("{0}")]
+    RaceError(#[from] RaceError),
+    #[error("There is no record {0} to race")]
+    MissingGhostRecord(i32),
+    #[error("Record {0} was saved without a seed, its text can't be typed again")]
+    GhostWithoutSeed(i32),
+    #[error("Record {0} was typed on the word list {1}, which is not in the word lists folder")]
+    MissingGhostWordList(i32, String),
     #[error("Encountered with error while handling keyboard events: {0}")]
     HandleEventError(DynamicError),
 }
 
+impl Default for Controller {
+    fn default() -> Self {
+        Controller::new()
+    }
+}
+
 impl Controller {
     pub fn new() -> Self {
         Self {
             state: State::new(),
-            client: Client::default(),
+            store: Box::new(Client::default()),
+            race: None,
+            race_progress: None,
+        }
+    }
+
+    /// Creates a controller that keeps its records in the store.
+    pub fn with_store(store: impl RecordStore + 'static) -> Self {
+        Self {
+            store: Box::new(store),
+            ..Controller::new()
         }
     }
 
+    /// Creates a controller whose timers read the time from the clock.
+    pub fn with_clock(clock: SharedClock) -> Self {
+        let mut controller = Controller::new();
+        controller.state.set_clock(clock);
+        controller
+    }
+
     pub fn handle_action(&mut self, action: Action) -> Result<(), ControllerError> {
         match action {
             Action::Init => {
@@ -50,110 +106,598 @@ impl Controller {
                 self.exit()?;
             }
             Action::CharInput(user_input) => {
-                let current_position = self.state.get_position() as usize;
-                if let Some(current_character) =
-                    self.state.get_paragraph().chars().nth(current_position)
-                {
-                    if current_character == user_input {
-                        self.state.set_position((current_position + 1) as i32);
-
-                        if current_character == ' ' {
-                            let word_count = self.state.get_word_count() + 1;
-                            self.state.set_word_count(word_count);
-                        } else {
-                            let char_count = self.state.get_char_count() + 1;
-                            self.state.set_char_count(char_count);
-                        }
-                    }
+                self.type_key(user_input);
+                if *self.state.get_page() == Page::Game && self.state.get_session().is_finished() {
+                    self.handle_action(Action::ChangePage(Page::GameResult))?;
                 }
             }
+            Action::DeleteChar => self.type_key(BACKSPACE_CHAR),
+            Action::DeleteWord => self.type_key(DELETE_WORD_CHAR),
             Action::ChangePage(page) => {
                 match page {
                     Page::CountDown => {
-                        let parsed_words = get_words()
+                        // every run gets a seed so its text can be typed again, the daily
+                        // challenge takes it from the date, a hot seat tournament from its
+                        // start and a ghost from its record
+                        let hot_seat = self
+                            .state
+                            .get_hot_seat()
+                            .as_ref()
+                            .map(|hot_seat| (hot_seat.seed, hot_seat.mode));
+                        if let Some((_, mode)) = hot_seat {
+                            self.state.set_mode(mode);
+                        }
+                        let fixed_seed = match (self.state.get_mode(), hot_seat) {
+                            (_, Some((seed, _))) => Some(seed),
+                            (Mode::Daily, None) => {
+                                Some(get_daily_seed(self.state.get_clock().today()))
+                            }
+                            _ => self.state.get_seed(),
+                        };
+                        // everyone in a race types the same text and players taking turns
+                        // get no help, so no ghosts
+                        let is_racing = self.race.is_some();
+                        let ghost_record = match is_racing || hot_seat.is_some() {
+                            true => None,
+                            false => self.get_ghost_record(fixed_seed)?,
+                        };
+                        if let Some(record) = ghost_record.as_ref() {
+                            if let Ok(mode) = record.mode.parse::<Mode>() {
+                                self.state.set_mode(mode);
+                            }
+                            self.restore_paragraph_settings(record)?;
+                        }
+                        let seed = ghost_record
+                            .as_ref()
+                            .and_then(|record| record.seed)
+                            .or(fixed_seed)
+                            .unwrap_or_else(rand::random);
+                        self.state.set_paragraph_seed(Some(seed));
+                        let parsed_paragraph = if let Mode::Quote(length) = self.state.get_mode() {
+                            let quotes = get_quotes(QUOTES)?;
+                            let quote = get_quote(&quotes, length, Some(seed))?;
+                            let text = quote.text.clone();
+                            self.state.set_quote(Some(quote));
+                            text
+                        } else if let Mode::Code(language) = self.state.get_mode() {
+                            self.state.set_quote(None);
+                            let snippets = get_snippets(SNIPPETS)?;
+                            get_snippet(&snippets, language, Some(seed))?.code
+                        } else {
+                            self.state.set_quote(None);
+                            // the daily and race texts only depend on the seed, not on the
+                            // settings of the player
+                            let (word_list_name, punctuation, numbers) = self
+                                .get_paragraph_settings()
+                                .unwrap_or((DEFAULT_WORD_LIST_NAME.to_string(), false, false));
+                            let word_list = match word_list_name == DEFAULT_WORD_LIST_NAME {
+                                true => WORD_LIST,
+                                false => self.state.get_word_list(),
+                            };
+                            // custom lists keep their casing, e.g. `Self` or product names
+                            let is_bundled_list = word_list_name == DEFAULT_WORD_LIST_NAME;
+                            let mut parsed_words = get_words(
+                                word_list,
+                                self.state.get_mode().get_paragraph_length(),
+                                Some(seed),
+                            )
                             .iter()
-                            .map(|word| word.to_string())
+                            .map(|word| {
+                                if is_bundled_list {
+                                    word.to_lowercase()
+                                } else {
+                                    word.to_string()
+                                }
+                            })
                             .collect::<Vec<String>>();
-                        let parsed_paragraph = parsed_words
-                            .iter()
-                            .map(|word| word.to_lowercase())
-                            .collect::<Vec<String>>()
-                            .join(" ");
+                            // offset the seed so the marks don't follow the word pick
+                            let mut rng = get_rng(Some(seed.wrapping_add(1)));
+                            if numbers {
+                                parsed_words = add_numbers(parsed_words, &mut rng);
+                            }
+                            if punctuation {
+                                parsed_words = add_punctuation(parsed_words, &mut rng);
+                            }
+                            parsed_words.join(" ")
+                        };
                         self.state.set_paragraph(parsed_paragraph);
+                        let ghost = match ghost_record {
+                            Some(record) => {
+                                let keystrokes = self.store.get_keystrokes(record.id)?;
+                                Some(Ghost::new(record, &keystrokes, self.state.get_paragraph()))
+                            }
+                            None => None,
+                        };
+                        self.state.set_ghost(ghost);
                         self.state.reset_stats();
                         self.state.set_reference_timestamp(None);
                     }
                     Page::Game => {
                         self.state.set_reference_timestamp(None);
                     }
-                    Page::Menu => {}
+                    Page::Menu | Page::Leaderboard => {}
+                    Page::Profiles => {
+                        let profiles = self.store.get_profiles()?;
+                        let profile_index = self
+                            .state
+                            .get_profile()
+                            .as_ref()
+                            .and_then(|profile| profiles.iter().position(|p| p == profile))
+                            .map_or(0, |index| index as i32 + 1);
+                        self.state.set_profiles(profiles);
+                        self.state.set_profile_index(profile_index);
+                        self.state.set_profile_name(String::new());
+                    }
                     Page::Records => {
+                        self.state.set_record_index(0);
                         self.handle_action(Action::GetRecords)?;
                     }
                     Page::GameResult => {
+                        self.sample_progress();
+                        let elapsed_millis = self.state.get_elapsed_millis();
+                        let duration = match self.state.get_mode() {
+                            Mode::Time(seconds) => elapsed_millis.min(seconds * 1000),
+                            Mode::Words(_) | Mode::Quote(_) | Mode::Code(_) | Mode::Daily => {
+                                elapsed_millis
+                            }
+                        };
+                        self.state.set_duration(duration);
+                        // read the best of the player before this run is saved
+                        let player_id = self.state.get_profile().as_ref().map(|profile| profile.id);
+                        let quote_best = match self.state.get_quote() {
+                            Some(quote) => self.store.get_quote_best(quote.id, player_id)?,
+                            None => None,
+                        };
+                        self.state.set_quote_best(quote_best);
                         self.handle_action(Action::PostRecord)?;
+                        self.send_race_progress(true)?;
+                        let result = HotSeatResult {
+                            name: self
+                                .state
+                                .get_profile()
+                                .as_ref()
+                                .map_or(GUEST_NAME.to_string(), |profile| profile.name.clone()),
+                            wpm: self.state.get_session().get_metrics(duration).wpm,
+                            accuracy: self.state.get_accuracy(),
+                        };
+                        if let Some(hot_seat) = self.state.get_hot_seat_mut() {
+                            hot_seat.results.push(result);
+                        }
                         self.handle_action(Action::ChangePage(Page::Menu))?;
                     }
                 }
                 self.state.set_page(page);
             }
             Action::GetRecords => {
-                let records = self.client.get_records()?;
-                self.state.set_records(records);
+                // guests see the records of everyone, only the page under the cursor is read
+                let player_id = self.state.get_profile().as_ref().map(|profile| profile.id);
+                let record_count = self.store.count_records(player_id)?;
+                let record_index = self
+                    .state
+                    .get_record_index()
+                    .clamp(0, (record_count as i32 - 1).max(0));
+                let query = RecordQuery {
+                    player_id,
+                    column: self.state.get_record_column(),
+                    ascending: self.state.get_record_ascending(),
+                    limit: RECORDS_PAGE_SIZE,
+                    offset: record_index as usize / RECORDS_PAGE_SIZE * RECORDS_PAGE_SIZE,
+                };
+                let best_query = RecordQuery {
+                    player_id,
+                    column: RecordColumn::Wpm,
+                    limit: 1,
+                    ..RecordQuery::default()
+                };
+                let record_best = self.store.get_record_page(&best_query)?;
+                self.state.set_records(self.store.get_record_page(&query)?);
+                self.state.set_record_count(record_count);
+                self.state.set_record_index(record_index);
+                self.state
+                    .set_record_best(record_best.first().map(|record| record.id));
+                self.state.set_player_bests(self.store.get_player_bests()?);
+            }
+            Action::RecordAction(step) => {
+                self.state
+                    .set_record_index(self.state.get_record_index() + step);
+                self.handle_action(Action::GetRecords)?;
+            }
+            Action::SortRecords(step) => {
+                let column_index = RECORD_COLUMNS
+                    .iter()
+                    .position(|column| *column == self.state.get_record_column())
+                    .unwrap_or_default() as i32;
+                let new_index = (column_index + step).rem_euclid(RECORD_COLUMNS.len() as i32);
+                self.state
+                    .set_record_column(RECORD_COLUMNS[new_index as usize]);
+                self.state.set_record_ascending(false);
+                self.state.set_record_index(0);
+                self.handle_action(Action::GetRecords)?;
+            }
+            Action::ReverseRecords => {
+                self.state
+                    .set_record_ascending(!self.state.get_record_ascending());
+                self.state.set_record_index(0);
+                self.handle_action(Action::GetRecords)?;
             }
 
             Action::PostRecord => {
-                let wpm = calculate_word_speed(self.state.get_word_count(), GAME_DURATION);
-                let cpm = calculate_char_speed(self.state.get_char_count(), GAME_DURATION);
-                let date = get_current_datetime();
-                self.client.create_record(wpm, cpm, date)?;
-            }
-            Action::MenuAction => {
-                let menu_index = self.state.get_menu_index();
-                let new_index = if menu_index <= 0 { 1 } else { 0 };
+                let metrics = self
+                    .state
+                    .get_session()
+                    .get_metrics(self.state.get_duration());
+                let (word_list, punctuation, numbers) = match self.get_paragraph_settings() {
+                    Some((name, punctuation, numbers)) => (Some(name), punctuation, numbers),
+                    None => (None, false, false),
+                };
+                let record = Record {
+                    wpm: metrics.wpm,
+                    cpm: metrics.cpm,
+                    date: get_current_datetime(),
+                    raw_wpm: metrics.raw_wpm,
+                    accuracy: metrics.accuracy,
+                    mode: self.state.get_mode().to_string(),
+                    consistency: metrics.consistency,
+                    burst_wpm: metrics.burst_wpm,
+                    slowest_word_time: metrics.slowest_word_time,
+                    quote_id: self.state.get_quote().as_ref().map(|quote| quote.id),
+                    seed: self.state.get_paragraph_seed(),
+                    player_id: self.state.get_profile().as_ref().map(|profile| profile.id),
+                    word_list,
+                    punctuation,
+                    numbers,
+                    ..Record::default()
+                };
+                let record_id = self.store.create_record(&record)?;
+                self.store
+                    .create_keystrokes(record_id, self.state.get_keystrokes())?;
+            }
+            Action::MenuAction(step) => {
+                let item_count = MENU_ITEMS.len() as i32;
+                let new_index = (self.state.get_menu_index() + step).rem_euclid(item_count);
                 self.state.set_menu_index(new_index);
             }
+            Action::ChangeMode(step) => {
+                let mode_count = MODES.len() as i32;
+                let mode_index = MODES
+                    .iter()
+                    .position(|mode| *mode == self.state.get_mode())
+                    .unwrap_or_default() as i32;
+                let new_index = (mode_index + step).rem_euclid(mode_count);
+                self.state.set_mode(MODES[new_index as usize]);
+            }
+            Action::TogglePunctuation => {
+                self.state.set_punctuation(!self.state.get_punctuation());
+            }
+            Action::ToggleNumbers => {
+                self.state.set_numbers(!self.state.get_numbers());
+            }
+            Action::ToggleGhost => {
+                let ghost_target = match self.state.get_ghost_target() {
+                    Some(_) => None,
+                    None => Some(GhostTarget::Best),
+                };
+                self.state.set_ghost_target(ghost_target);
+            }
+            Action::StartRace => {
+                if let Some(race) = self.race.as_mut() {
+                    race.send(&ClientMessage::Start {
+                        mode: self.state.get_mode().to_string(),
+                        countdown: self.state.get_config().countdown,
+                    })?;
+                }
+            }
+            Action::ProfileAction(step) => {
+                let row_count = self.state.get_profiles().len() as i32 + 1;
+                let new_index = (self.state.get_profile_index() + step).rem_euclid(row_count);
+                self.state.set_profile_index(new_index);
+            }
+            Action::ProfileNameInput(user_input) => {
+                let mut profile_name = self.state.get_profile_name().clone();
+                profile_name.push(user_input);
+                self.state.set_profile_name(profile_name);
+            }
+            Action::DeleteProfileNameChar => {
+                let mut profile_name = self.state.get_profile_name().clone();
+                profile_name.pop();
+                self.state.set_profile_name(profile_name);
+            }
+            Action::PickProfile => {
+                let name = self.state.get_profile_name().trim().to_string();
+                let profile = match name.is_empty() {
+                    // a typed name picks the profile of that name or creates it
+                    false => match self
+                        .state
+                        .get_profiles()
+                        .iter()
+                        .find(|profile| profile.name == name)
+                    {
+                        Some(profile) => Some(profile.clone()),
+                        None => Some(self.store.create_profile(&name)?),
+                    },
+                    true => match self.state.get_profile_index() {
+                        0 => None,
+                        index => self.state.get_profiles().get(index as usize - 1).cloned(),
+                    },
+                };
+                self.state.set_profile(profile);
+                self.state.set_profile_name(String::new());
+                // in a hot seat tournament the picked player types next
+                let page = match self.state.get_hot_seat() {
+                    Some(hot_seat) if !hot_seat.is_finished() => Page::CountDown,
+                    _ => Page::Menu,
+                };
+                self.handle_action(Action::ChangePage(page))?;
+            }
+            Action::ChangeHotSeat(step) => {
+                let sizes = [0]
+                    .into_iter()
+                    .chain(2..=MAX_HOT_SEAT_PLAYERS)
+                    .collect::<Vec<usize>>();
+                let size_index = sizes
+                    .iter()
+                    .position(|size| *size == self.state.get_hot_seat_size())
+                    .unwrap_or_default() as i32;
+                let new_index = (size_index + step).rem_euclid(sizes.len() as i32);
+                self.state.set_hot_seat_size(sizes[new_index as usize]);
+                // a tournament left halfway doesn't carry over
+                self.state.set_hot_seat(None);
+            }
+            Action::StartHotSeat => {
+                let seed = self.state.get_seed().unwrap_or_else(rand::random);
+                self.state.set_hot_seat(Some(HotSeat::new(
+                    self.state.get_hot_seat_size(),
+                    seed,
+                    self.state.get_mode(),
+                )));
+                self.handle_action(Action::ChangePage(Page::Profiles))?;
+            }
+            Action::EndHotSeat => {
+                self.state.set_hot_seat(None);
+                self.handle_action(Action::ChangePage(Page::Menu))?;
+            }
+            Action::ChangeWordList(step) => {
+                let word_lists = self.state.get_word_lists();
+                let list_count = word_lists.len() as i32 + 1;
+                let list_index = word_lists
+                    .iter()
+                    .position(|word_list| word_list.name == self.state.get_word_list_name())
+                    .map_or(0, |index| index as i32 + 1);
+                // the bundled list comes first
+                let new_index = (list_index + step).rem_euclid(list_count);
+                let word_list = match new_index {
+                    0 => None,
+                    _ => word_lists.get(new_index as usize - 1).cloned(),
+                };
+                self.state.set_word_list(word_list);
+            }
             Action::Empty => {}
         }
         Ok(())
     }
 
-    pub fn handle_key_stroke(&mut self, key_code: KeyCode) -> Action {
-        match key_code {
-            KeyCode::Esc => match self.state.get_page() {
-                Page::Menu => Action::Exit,
+    /// Returns the record the ghost replays. The best run only counts records of the mode
+    /// that saved a seed, and of the same seed when the run has a fixed one.
+    fn get_ghost_record(&self, fixed_seed: Option<u64>) -> Result<Option<Record>, ControllerError> {
+        let Some(ghost_target) = self.state.get_ghost_target() else {
+            return Ok(None);
+        };
+        let records = self.store.get_records()?;
+        match ghost_target {
+            GhostTarget::Best => {
+                let mode = self.state.get_mode().to_string();
+                Ok(records
+                    .into_iter()
+                    .filter(|record| record.mode == mode && record.seed.is_some())
+                    .filter(|record| fixed_seed.is_none() || record.seed == fixed_seed)
+                    .max_by_key(|record| record.wpm))
+            }
+            GhostTarget::Record(id) => {
+                let record = records
+                    .into_iter()
+                    .find(|record| record.id == id)
+                    .ok_or(ControllerError::MissingGhostRecord(id))?;
+                if record.seed.is_none() {
+                    return Err(ControllerError::GhostWithoutSeed(id));
+                }
+                Ok(Some(record))
+            }
+        }
+    }
+
+    /// Returns the word list name, punctuation and numbers the paragraph is built with.
+    /// Quotes, snippets, the daily challenge and races don't depend on them, the last two
+    /// always use the bundled list with no marks so everyone types the same text.
+    fn get_paragraph_settings(&self) -> Option<(String, bool, bool)> {
+        match self.state.get_mode() {
+            Mode::Quote(_) | Mode::Code(_) | Mode::Daily => None,
+            Mode::Time(_) | Mode::Words(_) if self.race.is_some() => None,
+            Mode::Time(_) | Mode::Words(_) => Some((
+                self.state.get_word_list_name().to_string(),
+                self.state.get_punctuation(),
+                self.state.get_numbers(),
+            )),
+        }
+    }
+
+    /// Switches to the word list and toggles the ghost's text was built with. Records saved
+    /// before they were stored keep the current settings.
+    fn restore_paragraph_settings(&mut self, record: &Record) -> Result<(), ControllerError> {
+        let Some(name) = record.word_list.as_ref() else {
+            return Ok(());
+        };
+        if name == DEFAULT_WORD_LIST_NAME {
+            self.state.set_word_list(None);
+        } else if name != self.state.get_word_list_name() {
+            let word_list = self
+                .state
+                .get_word_lists()
+                .iter()
+                .find(|word_list| word_list.name == *name)
+                .cloned()
+                .ok_or_else(|| ControllerError::MissingGhostWordList(record.id, name.clone()))?;
+            self.state.set_word_list(Some(word_list));
+        }
+        self.state.set_punctuation(record.punctuation);
+        self.state.set_numbers(record.numbers);
+        Ok(())
+    }
+
+    /// Passes a key to the typing session, keystrokes are only kept during the test.
+    fn type_key(&mut self, key: char) {
+        let timestamp = self.state.get_elapsed_millis();
+        let is_game = *self.state.get_page() == Page::Game;
+        let session = self.state.get_session_mut();
+        if is_game {
+            session.type_char(key, timestamp);
+        } else {
+            session.press(key, timestamp);
+        }
+    }
+
+    /// Closes a sample for every full second of the test that has none yet.
+    pub fn sample_progress(&mut self) {
+        let mut elapsed_millis = self.state.get_elapsed_millis();
+        if let Mode::Time(seconds) = self.state.get_mode() {
+            elapsed_millis = elapsed_millis.min(seconds * 1000);
+        }
+        self.state.get_session_mut().sample(elapsed_millis);
+    }
+
+    pub fn handle_key_stroke(&mut self, key: KeyEvent) -> Action {
+        let key = self
+            .state
+            .get_config()
+            .key_bindings
+            .resolve(key, self.state.get_page());
+        let is_control = key.modifiers.intersects(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => match (self.state.get_page(), self.state.get_hot_seat()) {
+                (Page::Menu, _) => Action::Exit,
+                (Page::Leaderboard, _) => Action::EndHotSeat,
+                // backing out of a hot seat tournament ends it, the last result still
+                // shows the leaderboard
+                (Page::GameResult, Some(hot_seat)) if hot_seat.is_finished() => {
+                    Action::ChangePage(Page::Leaderboard)
+                }
+                (_, Some(_)) => Action::EndHotSeat,
                 _ => Action::ChangePage(Page::Menu),
             },
             KeyCode::Enter => match self.state.get_page() {
-                Page::Menu => {
-                    if self.state.get_menu_index() == 0 {
-                        Action::ChangePage(Page::CountDown)
+                Page::Game if self.is_code_mode() => Action::CharInput('\n'),
+                Page::Menu => match self.get_menu_item() {
+                    // in a race the host starts the test for everyone
+                    "Start" => match self.state.get_race_player_id() {
+                        Some(_) if self.state.get_is_race_host() => Action::StartRace,
+                        Some(_) => Action::Empty,
+                        None if self.state.get_hot_seat_size() > 0 => Action::StartHotSeat,
+                        None => Action::ChangePage(Page::CountDown),
+                    },
+                    "Profile" => Action::ChangePage(Page::Profiles),
+                    "Records" => Action::ChangePage(Page::Records),
+                    _ => self.get_menu_option_action(1),
+                },
+                Page::GameResult => match self.state.get_hot_seat() {
+                    Some(hot_seat) if hot_seat.is_finished() => {
+                        Action::ChangePage(Page::Leaderboard)
+                    }
+                    Some(_) => Action::ChangePage(Page::Profiles),
+                    None => Action::ChangePage(Page::Menu),
+                },
+                Page::Profiles => Action::PickProfile,
+                Page::Leaderboard => Action::EndHotSeat,
+                Page::Records => Action::ReverseRecords,
+                _ => Action::Empty,
+            },
+            // terminals report ctrl+backspace as ctrl+h, ctrl+w is the readline equivalent
+            KeyCode::Char('h') | KeyCode::Char('w') if is_control => match self.state.get_page() {
+                Page::Game => Action::DeleteWord,
+                _ => Action::Empty,
+            },
+            KeyCode::Char(user_input) => match self.state.get_page() {
+                Page::Game if !is_control => Action::CharInput(user_input),
+                Page::Profiles if !is_control => Action::ProfileNameInput(user_input),
+                _ => Action::Empty,
+            },
+            KeyCode::Tab => match self.state.get_page() {
+                Page::Game if self.is_code_mode() => Action::CharInput('\t'),
+                _ => Action::Empty,
+            },
+            KeyCode::Backspace => match self.state.get_page() {
+                Page::Game => {
+                    if is_control || key.modifiers.intersects(KeyModifiers::ALT) {
+                        Action::DeleteWord
                     } else {
-                        Action::ChangePage(Page::Records)
+                        Action::DeleteChar
                     }
                 }
-                Page::GameResult => Action::ChangePage(Page::Menu),
+                Page::Profiles => Action::DeleteProfileNameChar,
                 _ => Action::Empty,
             },
-            KeyCode::Char(user_input) => match self.state.get_page() {
-                Page::Game => Action::CharInput(user_input),
+            KeyCode::Down => match self.state.get_page() {
+                Page::Menu => Action::MenuAction(1),
+                Page::Profiles => Action::ProfileAction(1),
+                Page::Records => Action::RecordAction(1),
+                _ => Action::Empty,
+            },
+            KeyCode::Up => match self.state.get_page() {
+                Page::Menu => Action::MenuAction(-1),
+                Page::Profiles => Action::ProfileAction(-1),
+                Page::Records => Action::RecordAction(-1),
+                _ => Action::Empty,
+            },
+            KeyCode::PageDown => match self.state.get_page() {
+                Page::Records => Action::RecordAction(RECORDS_PAGE_SIZE as i32),
+                _ => Action::Empty,
+            },
+            KeyCode::PageUp => match self.state.get_page() {
+                Page::Records => Action::RecordAction(-(RECORDS_PAGE_SIZE as i32)),
                 _ => Action::Empty,
             },
-            KeyCode::Down | KeyCode::Up => match self.state.get_page() {
-                Page::Menu => Action::MenuAction,
+            KeyCode::Left => match self.state.get_page() {
+                Page::Menu => self.get_menu_option_action(-1),
+                Page::Records => Action::SortRecords(-1),
+                _ => Action::Empty,
+            },
+            KeyCode::Right => match self.state.get_page() {
+                Page::Menu => self.get_menu_option_action(1),
+                Page::Records => Action::SortRecords(1),
                 _ => Action::Empty,
             },
             _ => Action::Empty,
         }
     }
 
-    pub fn handle_events(&mut self) -> Result<(), DynamicError> {
-        if event::poll(std::time::Duration::from_millis(16))? {
-            if let event::Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    let action = self.handle_key_stroke(key.code);
-                    self.handle_action(action)?;
-                }
+    fn is_code_mode(&self) -> bool {
+        matches!(self.state.get_mode(), Mode::Code(_))
+    }
+
+    fn get_menu_item(&self) -> &str {
+        MENU_ITEMS
+            .get(self.state.get_menu_index() as usize)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns the action that steps the option under the menu cursor.
+    fn get_menu_option_action(&self, step: i32) -> Action {
+        match self.get_menu_item() {
+            "Mode" => Action::ChangeMode(step),
+            "Word list" => Action::ChangeWordList(step),
+            "Punctuation" => Action::TogglePunctuation,
+            "Numbers" => Action::ToggleNumbers,
+            "Ghost" => Action::ToggleGhost,
+            "Hot seat" => Action::ChangeHotSeat(step),
+            _ => Action::Empty,
+        }
+    }
+
+    pub fn handle_events(&mut self, events: &mut impl EventSource) -> Result<(), DynamicError> {
+        let timeout = std::time::Duration::from_millis(EVENT_POLL_MILLIS);
+        if let Some(Event::Key(key)) = events.next_event(timeout)? {
+            if key.kind == KeyEventKind::Press {
+                let action = self.handle_key_stroke(key);
+                self.handle_action(action)?;
             }
         }
         Ok(())
@@ -165,28 +709,284 @@ impl Controller {
         db_name: &str,
     ) -> Result<(), ControllerError> {
         self.handle_action(Action::Init)?;
-        self.client.open_connection(app_config_path, db_name)?;
-        self.client.create_records_table()?;
+        self.store.open(app_config_path, db_name)?;
+        Ok(())
+    }
+
+    /// Applies `config.toml` from the config folder if there is one. The defaults stay in
+    /// place when it can't be parsed.
+    pub fn load_config(&mut self, app_config_path: &Path) -> Result<(), ControllerError> {
+        if !file_exists(app_config_path, CONFIG_NAME) {
+            return Ok(());
+        }
+        let content = read_file(&app_config_path.join(CONFIG_NAME))?;
+        let config = Config::parse(&content)?;
+        self.state.set_mode(config.mode);
+        self.state.set_punctuation(config.punctuation);
+        self.state.set_numbers(config.numbers);
+        self.state.set_config(config);
+
+        if let Some(word_list) = self.state.get_config().word_list.clone() {
+            self.select_word_list(&word_list, app_config_path)?;
+        }
+        Ok(())
+    }
+
+    /// Loads the valid lists of the word lists folder. Invalid files are left out and the
+    /// first of their errors is returned.
+    pub fn load_word_lists(&mut self, app_config_path: &Path) -> Result<(), ControllerError> {
+        let paths = get_word_list_paths(&app_config_path.join(WORD_LISTS_PATH))?;
+        let (word_lists, errors): (Vec<_>, Vec<_>) = paths
+            .iter()
+            .map(|path| load_word_list(path))
+            .partition(|result| result.is_ok());
+        self.state
+            .set_word_lists(word_lists.into_iter().filter_map(Result::ok).collect());
+
+        match errors.into_iter().find_map(Result::err) {
+            Some(error) => Err(error.into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Picks a list of the word lists folder by name, or reads it from a path relative to
+    /// `base_path` when there is no such list.
+    pub fn select_word_list(
+        &mut self,
+        word_list: &str,
+        base_path: &Path,
+    ) -> Result<(), ControllerError> {
+        let name = word_list.strip_suffix(".txt").unwrap_or(word_list);
+        if name == DEFAULT_WORD_LIST_NAME {
+            self.state.set_word_list(None);
+            return Ok(());
+        }
+        let word_list = match self
+            .state
+            .get_word_lists()
+            .iter()
+            .find(|word_list| word_list.name == name)
+        {
+            Some(word_list) => word_list.clone(),
+            None => load_word_list(&base_path.join(word_list))?,
+        };
+        self.state.set_word_list(Some(word_list));
+        Ok(())
+    }
+
+    /// Applies the command line flags on top of the config for this run.
+    pub fn apply_args(&mut self, cli: &Cli) -> Result<(), ControllerError> {
+        if let Some(mode) = cli.get_mode() {
+            self.state.set_mode(mode);
+        }
+        if cli.punctuation {
+            self.state.set_punctuation(true);
+        }
+        if cli.numbers {
+            self.state.set_numbers(true);
+        }
+        if let Some(word_list) = &cli.wordlist {
+            self.select_word_list(&word_list.to_string_lossy(), Path::new(""))?;
+        }
+        self.state.set_seed(cli.seed);
+        if let Some(ghost_target) = cli.ghost {
+            self.state.set_ghost_target(Some(ghost_target));
+        }
+        Ok(())
+    }
+
+    /// Opens the profile picker before the menu once the install has profiles.
+    fn pick_profile_on_start(&mut self) -> Result<(), ControllerError> {
+        if self.race.is_some() || self.store.get_profiles()?.is_empty() {
+            return Ok(());
+        }
+        self.handle_action(Action::ChangePage(Page::Profiles))
+    }
+
+    /// Hosts or joins the race of the `host` and `join` subcommands.
+    fn start_race(&mut self, cli: &Cli) -> Result<(), ControllerError> {
+        let get_name = |name: &Option<String>| {
+            name.clone()
+                .or_else(|| env::var("USER").ok())
+                .unwrap_or(DEFAULT_PLAYER_NAME.to_string())
+        };
+        match &cli.command {
+            Some(Command::Host { port, name }) => {
+                self.host_race(*port, &get_name(name))?;
+            }
+            Some(Command::Join { addr, name }) => {
+                self.join_race(addr.as_str(), &get_name(name))?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Starts a race server on the port and joins it as the host.
+    pub fn host_race(&mut self, port: u16, name: &str) -> Result<u16, ControllerError> {
+        let server = RaceServer::bind(("0.0.0.0", port))?;
+        let port = server.local_addr()?.port();
+        let host_token = server.host_token();
+        server.spawn();
+        self.connect_race(("127.0.0.1", port), name, Some(host_token))?;
+        Ok(port)
+    }
+
+    pub fn join_race(
+        &mut self,
+        address: impl ToSocketAddrs,
+        name: &str,
+    ) -> Result<(), ControllerError> {
+        self.connect_race(address, name, None)
+    }
+
+    fn connect_race(
+        &mut self,
+        address: impl ToSocketAddrs,
+        name: &str,
+        host_token: Option<u64>,
+    ) -> Result<(), ControllerError> {
+        self.race = Some(RaceClient::connect(address, name, host_token)?);
+        self.race_progress = None;
+        Ok(())
+    }
+
+    /// Handles the messages the race server sent since the last call and shares the
+    /// progress of a running test.
+    pub fn poll_race(&mut self) -> Result<(), ControllerError> {
+        let Some(race) = &self.race else {
+            return Ok(());
+        };
+        let messages = std::iter::from_fn(|| race.try_recv()).collect::<Vec<ServerMessage>>();
+        for message in messages {
+            match message {
+                ServerMessage::Welcome { player_id, is_host } => {
+                    self.state.set_race_player_id(Some(player_id));
+                    self.state.set_is_race_host(is_host);
+                }
+                ServerMessage::Players { players } => {
+                    self.state.set_race_players(players);
+                }
+                ServerMessage::Start {
+                    seed,
+                    mode,
+                    countdown,
+                } => {
+                    if let Ok(mode) = mode.parse::<Mode>() {
+                        self.state.set_mode(mode);
+                    }
+                    self.state.set_seed(Some(seed));
+                    // everyone counts down from the countdown of the host
+                    let mut config = self.state.get_config().clone();
+                    config.countdown = countdown;
+                    self.state.set_config(config);
+                    self.race_progress = None;
+                    self.handle_action(Action::ChangePage(Page::CountDown))?;
+                }
+                ServerMessage::Closed => self.leave_race()?,
+            }
+        }
+        if *self.state.get_page() == Page::Game {
+            self.send_race_progress(false)?;
+        }
+        Ok(())
+    }
+
+    fn send_race_progress(&mut self, finished: bool) -> Result<(), ControllerError> {
+        let Some(race) = self.race.as_mut() else {
+            return Ok(());
+        };
+        let duration = match finished {
+            true => self.state.get_duration(),
+            false => self.state.get_elapsed_millis(),
+        };
+        let progress = Progress {
+            position: self.state.get_position() as usize,
+            wpm: calculate_word_speed(self.state.get_char_count(), duration),
+            finished,
+        };
+        if self.race_progress == Some(progress) {
+            return Ok(());
+        }
+        if race.send(&ClientMessage::Progress(progress)).is_err() {
+            // the server is gone, its closing message may not have been read yet
+            return self.leave_race();
+        }
+        self.race_progress = Some(progress);
+        Ok(())
+    }
+
+    /// Drops the race once the host left, a running race ends and the player types alone.
+    fn leave_race(&mut self) -> Result<(), ControllerError> {
+        self.race = None;
+        self.race_progress = None;
+        self.state.set_race_player_id(None);
+        self.state.set_is_race_host(false);
+        self.state.set_race_players(Vec::new());
+        if matches!(self.state.get_page(), Page::CountDown | Page::Game) {
+            self.handle_action(Action::ChangePage(Page::Menu))?;
+        }
         Ok(())
     }
 
     pub fn exit(&mut self) -> Result<(), ControllerError> {
-        self.client.close_connection()?;
+        self.store.close()?;
         Ok(())
     }
 
-    pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<(), ControllerError> {
+    /// Runs the app with the config folder of the user until it exits. Only the folder
+    /// lookup happens here, tests call `run_in` with a test folder instead so they never
+    /// touch the real config and records.
+    pub fn run<B: Backend>(
+        &mut self,
+        terminal: &mut Terminal<B>,
+        events: &mut impl EventSource,
+        cli: &Cli,
+    ) -> Result<(), ControllerError> {
         let app_config_path = get_app_config_path()?;
+        self.run_in(terminal, events, cli, app_config_path)
+    }
+
+    /// Runs the app with its config folder at `app_config_path` until it exits.
+    pub fn run_in<B: Backend>(
+        &mut self,
+        terminal: &mut Terminal<B>,
+        events: &mut impl EventSource,
+        cli: &Cli,
+        app_config_path: PathBuf,
+    ) -> Result<(), ControllerError> {
         create_config_folder(&app_config_path)?;
+        let _ = self
+            .load_word_lists(&app_config_path)
+            .map_err(|e| self.state.set_error(e.to_string()));
+        let _ = self
+            .load_config(&app_config_path)
+            .map_err(|e| self.state.set_error(e.to_string()));
+        let _ = self
+            .apply_args(cli)
+            .map_err(|e| self.state.set_error(e.to_string()));
+        if cli.no_save {
+            self.store = Box::new(MemoryStore::new());
+        }
+        let (db_folder, db_name) = cli.get_db_location(app_config_path);
         // this line sets the global error
         let _ = self
-            .init_controller(app_config_path, DB_NAME)
+            .init_controller(db_folder, &db_name)
+            .map_err(|e| self.state.set_error(e.to_string()));
+        let _ = self
+            .start_race(cli)
+            .map_err(|e| self.state.set_error(e.to_string()));
+        let _ = self
+            .pick_profile_on_start()
             .map_err(|e| self.state.set_error(e.to_string()));
         while self.state.get_is_running() {
-            let _ = self.handle_events().map_err(|e| {
+            let _ = self.handle_events(events).map_err(|e| {
                 self.state
                     .set_error(ControllerError::HandleEventError(e).to_string())
             });
+            let _ = self
+                .poll_race()
+                .map_err(|e| self.state.set_error(e.to_string()));
             View::draw(terminal, &self.state)
                 .map_err(|e| {
                     self.state
@@ -194,27 +994,34 @@ impl Controller {
                 })
                 .ok();
 
-            self.state.set_timer(Local::now().timestamp_millis());
+            self.tick()
+                .map_err(|e| self.state.set_error(e.to_string()))
+                .ok();
+        }
+        Ok(())
+    }
 
-            if self.state.get_reference_timestamp() != 0 {
-                match self.state.get_page() {
-                    Page::CountDown => {
-                        if self.state.get_elapsed_time() >= COUNTDOWN_DURATION {
-                            self.handle_action(Action::ChangePage(Page::Game))
-                                .map_err(|e| self.state.set_error(e.to_string()))
-                                .ok();
-                        }
-                    }
-                    Page::Game => {
-                        if self.state.get_elapsed_time() >= GAME_DURATION {
-                            self.handle_action(Action::ChangePage(Page::GameResult))
-                                .map_err(|e| self.state.set_error(e.to_string()))
-                                .ok();
-                        }
+    /// Moves the timers on, ending the countdown and timed tests once their time is up.
+    pub fn tick(&mut self) -> Result<(), ControllerError> {
+        let now = self.state.get_clock().now_millis();
+        self.state.set_timer(now);
+        if self.state.get_reference_timestamp() == 0 {
+            return Ok(());
+        }
+        let elapsed_millis = self.state.get_elapsed_millis();
+        match self.state.get_page() {
+            Page::CountDown if elapsed_millis >= self.state.get_config().countdown * 1000 => {
+                self.handle_action(Action::ChangePage(Page::Game))?;
+            }
+            Page::Game => {
+                self.sample_progress();
+                if let Mode::Time(seconds) = self.state.get_mode() {
+                    if elapsed_millis >= seconds * 1000 {
+                        self.handle_action(Action::ChangePage(Page::GameResult))?;
                     }
-                    _ => {}
                 }
             }
+            _ => {}
         }
         Ok(())
     }
@@ -223,8 +1030,16 @@ impl Controller {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::constants::{TEST_APP_PATH, TEST_DB_NAME};
+    use crate::clock::{Clock, ManualClock};
+    use crate::config::KeyBindings;
+    use crate::constants::{Language, QuoteLength, DAILY_WORD_COUNT, TEST_APP_PATH, TEST_DB_NAME};
+    use crate::events::ScriptedEvents;
+    use crate::keystroke::Keystroke;
+    use crate::util::split_words;
+    use chrono::{Local, TimeZone};
+    use ratatui::backend::TestBackend;
     use std::path::{Path, PathBuf};
+    use unicode_segmentation::UnicodeSegmentation;
 
     fn get_test_db_path() -> PathBuf {
         let db_path = Path::new(TEST_APP_PATH);
@@ -232,19 +1047,12 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison, clippy::len_zero)]
     fn test_handle_action() {
         // CONTROLLER RUNNING STATE TEST
-        let mut controller = Controller::new();
+        let mut controller = Controller::with_store(MemoryStore::new());
         assert_eq!(controller.state.get_is_running(), false);
 
-        let db_name = format!("controller_{TEST_DB_NAME}");
-        let result = controller
-            .client
-            .open_connection(get_test_db_path(), db_name.as_str());
-        assert!(result.is_ok());
-        let result = controller.client.create_records_table();
-        assert!(result.is_ok());
-
         // INIT CONTROLLER TEST
         let action = Action::Init;
         let result = controller.handle_action(action);
@@ -260,15 +1068,25 @@ mod tests {
         assert_eq!(controller.state.get_position(), 1);
         assert_eq!(controller.state.get_char_count(), 1);
         assert_eq!(controller.state.get_word_count(), 0);
+        assert_eq!(controller.state.get_correct_count(), 1);
 
         // HANDLE WRONG CHAR INPUT TEST
         let character = 'T';
         let action = Action::CharInput(character);
         let result = controller.handle_action(action);
         assert!(result.is_ok());
+        assert_eq!(controller.state.get_input(), "TT");
         assert_eq!(controller.state.get_position(), 1);
         assert_eq!(controller.state.get_char_count(), 1);
         assert_eq!(controller.state.get_word_count(), 0);
+        assert_eq!(controller.state.get_extra_count(), 1);
+
+        // HANDLE DELETE CHAR TEST
+        let action = Action::DeleteChar;
+        let result = controller.handle_action(action);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_input(), "T");
+        assert_eq!(controller.state.get_position(), 1);
 
         // HANDLE SPACE INPUT TEST
         let character = ' ';
@@ -276,6 +1094,69 @@ mod tests {
         let result = controller.handle_action(action);
         assert!(result.is_ok());
         assert_eq!(controller.state.get_word_count(), 1);
+        assert_eq!(controller.state.get_char_count(), 2);
+        assert_eq!(controller.state.get_correct_count(), 2);
+        assert_eq!(controller.state.get_position(), 2);
+
+        // HANDLE REPEATED SPACE INPUT TEST
+        let action = Action::CharInput(' ');
+        let result = controller.handle_action(action);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_input(), "T ");
+
+        // HANDLE WRONG WORD AND DELETE WORD TEST
+        let result = controller.handle_action(Action::CharInput('X'));
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_char_count(), 2);
+        assert_eq!(controller.state.get_incorrect_count(), 1);
+        assert_eq!(controller.state.get_position(), 3);
+        let action = Action::DeleteWord;
+        let result = controller.handle_action(action);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_input(), "T ");
+        assert_eq!(controller.state.get_position(), 2);
+        let action = Action::DeleteWord;
+        let result = controller.handle_action(action);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_input(), "");
+        assert_eq!(controller.state.get_position(), 0);
+        assert_eq!(controller.state.get_word_count(), 0);
+        // deleting input doesn't undo keystrokes
+        assert_eq!(controller.state.get_typed_count(), 4);
+
+        // HANDLE SKIPPED WORD TEST
+        controller.state.set_paragraph("TEST TEST".to_string());
+        let result = controller.handle_action(Action::CharInput('T'));
+        assert!(result.is_ok());
+        let result = controller.handle_action(Action::CharInput(' '));
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_missed_count(), 3);
+        assert_eq!(controller.state.get_correct_count(), 3);
+        controller.state.reset_stats();
+        controller.state.set_paragraph("T E S T".to_string());
+
+        // HANDLE SPACE AT WORD START TEST
+        let action = Action::CharInput(' ');
+        let result = controller.handle_action(action);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_input(), "");
+
+        // HANDLE SPACE AFTER LAST WORD TEST
+        for character in "T E S T".chars() {
+            let result = controller.handle_action(Action::CharInput(character));
+            assert!(result.is_ok());
+        }
+        let action = Action::CharInput(' ');
+        let result = controller.handle_action(action);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_input(), "T E S T");
+
+        // HANDLE DELETE CHAR ON EMPTY INPUT TEST
+        controller.state.reset_stats();
+        let action = Action::DeleteChar;
+        let result = controller.handle_action(action);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_position(), 0);
 
         // PAGE CHANGE TEST
         // PAGE::COUNTDOWN
@@ -284,6 +1165,7 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(controller.state.get_page(), &Page::CountDown);
         assert!(controller.state.get_paragraph().len() > 0);
+        assert!(controller.state.get_input().is_empty());
 
         // PAGE::GAME
         let action = Action::ChangePage(Page::Game);
@@ -327,109 +1209,1294 @@ mod tests {
 
         // MENU ACTION TEST
         assert_eq!(controller.state.get_menu_index(), 0);
-        let action = Action::MenuAction;
+        let action = Action::MenuAction(1);
         let result = controller.handle_action(action);
         assert!(result.is_ok());
         assert_eq!(controller.state.get_menu_index(), 1);
-
-        // EMPTY ACTION TEST
-        let action = Action::Empty;
+        let action = Action::MenuAction(-1);
         let result = controller.handle_action(action);
         assert!(result.is_ok());
-
-        // DROP TABLE
-        let result = controller.client.drop_records_table();
+        assert_eq!(controller.state.get_menu_index(), 0);
+        let action = Action::MenuAction(-1);
+        let result = controller.handle_action(action);
         assert!(result.is_ok());
+        assert_eq!(controller.state.get_menu_index(), 8);
 
-        // EXIT TEST
-        let action = Action::Exit;
+        // CHANGE MODE TEST
+        assert_eq!(controller.state.get_mode(), Mode::Time(60));
+        let action = Action::ChangeMode(1);
         let result = controller.handle_action(action);
         assert!(result.is_ok());
-        assert_eq!(controller.state.get_is_running(), false);
-        assert!(controller.client.connection.is_none());
-    }
-
-    #[test]
-    fn test_handle_key_stroke() {
-        // ESC KEY -- MENU PAGE TEST
-        let mut controller = Controller::new();
-        controller.state.set_page(Page::Menu);
-        let action = controller.handle_key_stroke(KeyCode::Esc);
-        assert_eq!(action, Action::Exit);
+        assert_eq!(controller.state.get_mode(), Mode::Time(120));
+        let action = Action::ChangeMode(1);
+        let result = controller.handle_action(action);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_mode(), Mode::Words(10));
+        let action = Action::ChangeMode(-5);
+        let result = controller.handle_action(action);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_mode(), Mode::Daily);
+        let action = Action::ChangeMode(-5);
+        let result = controller.handle_action(action);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_mode(), Mode::Quote(QuoteLength::Long));
+        let action = Action::ChangeMode(-3);
+        let result = controller.handle_action(action);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_mode(), Mode::Words(100));
 
-        // ESC KEY -- GAME PAGE TEST
-        controller.state.set_page(Page::Game);
-        let action = controller.handle_key_stroke(KeyCode::Esc);
-        assert_eq!(action, Action::ChangePage(Page::Menu));
+        // WORDS MODE TEST
+        controller.state.set_mode(Mode::Words(10));
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_paragraph().split(' ').count(), 10);
+        let result = controller.handle_action(Action::ChangePage(Page::Game));
+        assert!(result.is_ok());
+        let paragraph = controller.state.get_paragraph().clone();
+        let result = controller.handle_action(Action::CharInput('#'));
+        assert!(result.is_ok());
+        let result = controller.handle_action(Action::DeleteChar);
+        assert!(result.is_ok());
+        for character in paragraph.chars() {
+            let result = controller.handle_action(Action::CharInput(character));
+            assert!(result.is_ok());
+        }
+        assert_eq!(controller.state.get_page(), &Page::GameResult);
+        assert_eq!(
+            controller.state.get_accuracy(),
+            paragraph.len() as f64 / (paragraph.len() + 1) as f64 * 100.0
+        );
+        let result = controller.handle_action(Action::GetRecords);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_records().len(), 3);
+        // the newest record comes first
+        let record = &controller.state.get_records()[0];
+        assert_eq!(record.mode, "words 10");
+        assert!(record.slowest_word_time >= 0);
 
-        // ENTER KEY -- MENU PAGE TEST
-        controller.state.set_page(Page::Menu);
-        let action = controller.handle_key_stroke(KeyCode::Enter);
-        assert_eq!(action, Action::ChangePage(Page::CountDown));
-        controller.state.set_menu_index(1);
-        let action = controller.handle_key_stroke(KeyCode::Enter);
-        assert_eq!(action, Action::ChangePage(Page::Records));
+        // KEYSTROKE LOG TEST
+        let keystrokes = controller.state.get_keystrokes();
+        assert_eq!(keystrokes.len(), paragraph.len() + 2);
+        assert_eq!(keystrokes[0].typed, '#');
+        assert!(!keystrokes[0].is_correct);
+        assert_eq!(keystrokes[1].typed, BACKSPACE_CHAR);
+        assert_eq!(
+            keystrokes[2].expected,
+            paragraph.chars().next().map(String::from)
+        );
+        assert!(keystrokes[2..].iter().all(|keystroke| keystroke.is_correct));
+        let saved_keystrokes = controller.store.get_keystrokes(record.id).unwrap();
+        assert_eq!(saved_keystrokes.len(), keystrokes.len());
+        assert_eq!(saved_keystrokes[1].typed, BACKSPACE_CHAR);
+        assert_eq!(
+            saved_keystrokes.last().unwrap().expected,
+            paragraph.chars().last().map(String::from)
+        );
 
-        // ENTER KEY -- GAME RESULT PAGE TEST
+        // QUOTE MODE TEST
+        controller.state.set_mode(Mode::Quote(QuoteLength::Short));
+        controller.state.set_seed(Some(3));
+        for run in 0..2 {
+            let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+            assert!(result.is_ok());
+            let quote = controller.state.get_quote().clone().unwrap();
+            assert_eq!(controller.state.get_paragraph(), &quote.text);
+            assert_eq!(quote.get_length(), QuoteLength::Short);
+            let result = controller.handle_action(Action::ChangePage(Page::Game));
+            assert!(result.is_ok());
+            for character in quote.text.chars() {
+                let result = controller.handle_action(Action::CharInput(character));
+                assert!(result.is_ok());
+            }
+            assert_eq!(controller.state.get_page(), &Page::GameResult);
+            let result = controller.handle_action(Action::GetRecords);
+            assert!(result.is_ok());
+            let record = controller.state.get_records().first().unwrap();
+            assert_eq!(record.mode, "quote short");
+            assert_eq!(record.quote_id, Some(quote.id));
+            // the first run has no best to compare against
+            match run {
+                0 => assert_eq!(controller.state.get_quote_best(), None),
+                _ => assert!(controller.state.get_quote_best().is_some()),
+            }
+        }
+        controller.state.set_seed(None);
+        controller.state.set_mode(Mode::Words(10));
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_quote(), &None);
+
+        // CODE MODE TEST
+        controller.state.set_mode(Mode::Code(Language::Rust));
+        controller.state.set_seed(Some(3));
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(result.is_ok());
+        let paragraph = controller.state.get_paragraph().clone();
+        assert!(paragraph.contains("\n    "));
+        let result = controller.handle_action(Action::ChangePage(Page::Game));
+        assert!(result.is_ok());
+        // the indentation is filled in after every line break, typing it again is ignored
+        for character in paragraph.chars() {
+            let result = controller.handle_action(Action::CharInput(character));
+            assert!(result.is_ok());
+        }
+        assert_eq!(controller.state.get_page(), &Page::GameResult);
+        let result = controller.handle_action(Action::GetRecords);
+        assert!(result.is_ok());
+        let record = controller.state.get_records().first().unwrap();
+        assert_eq!(record.mode, "code rust");
+        assert_eq!(record.accuracy, 100.0);
+        assert_eq!(record.seed, Some(3));
+        let record = record.clone();
+        controller.state.set_seed(None);
+
+        // GHOST TEST
+        let result = controller.handle_action(Action::ToggleGhost);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_ghost_target(), Some(GhostTarget::Best));
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(result.is_ok());
+        let ghost = controller.state.get_ghost().clone().unwrap();
+        assert_eq!(ghost.record.mode, "code rust");
+        assert_eq!(controller.state.get_paragraph_seed(), ghost.record.seed);
+        controller.state.set_mode(Mode::Words(10));
+        controller
+            .state
+            .set_ghost_target(Some(GhostTarget::Record(record.id)));
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(result.is_ok());
+        // racing a record types its text again in its mode
+        assert_eq!(controller.state.get_mode(), Mode::Code(Language::Rust));
+        assert_eq!(controller.state.get_paragraph(), &paragraph);
+        let ghost = controller.state.get_ghost().clone().unwrap();
+        assert_eq!(ghost.record.id, record.id);
+        assert_eq!(
+            ghost.get_position(i64::MAX),
+            paragraph.graphemes(true).count()
+        );
+        controller
+            .state
+            .set_ghost_target(Some(GhostTarget::Record(-1)));
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(matches!(
+            result,
+            Err(ControllerError::MissingGhostRecord(-1))
+        ));
+        let result = controller.handle_action(Action::ToggleGhost);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_ghost_target(), None);
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_ghost(), &None);
+
+        // DAILY MODE TEST
+        let noon = Local.with_ymd_and_hms(2024, 1, 31, 12, 0, 0).unwrap();
+        controller
+            .state
+            .set_clock(SharedClock::new(ManualClock::new(noon.timestamp_millis())));
+        controller.state.set_mode(Mode::Daily);
+        controller.state.set_punctuation(true);
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(result.is_ok());
+        let daily_seed = get_daily_seed(noon.date_naive());
+        assert_eq!(controller.state.get_paragraph_seed(), Some(daily_seed));
+        let paragraph = controller.state.get_paragraph().clone();
+        assert_eq!(paragraph.split(' ').count(), DAILY_WORD_COUNT);
+        // the settings don't change the text of the day
+        assert!(!paragraph.contains('.'));
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_paragraph(), &paragraph);
+        controller.state.set_punctuation(false);
+        controller.state.set_clock(SharedClock::default());
+
+        // REPEAT SEED TEST
+        controller.state.set_mode(Mode::Words(25));
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(result.is_ok());
+        let paragraph = controller.state.get_paragraph().clone();
+        let seed = controller.state.get_paragraph_seed();
+        assert!(seed.is_some());
+        controller.state.set_seed(seed);
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_paragraph(), &paragraph);
+        controller.state.set_seed(None);
+
+        // PROFILES TEST
+        let result = controller.handle_action(Action::ChangePage(Page::Profiles));
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_page(), &Page::Profiles);
+        assert_eq!(controller.state.get_profile_index(), 0);
+        for character in "anx".chars() {
+            let result = controller.handle_action(Action::ProfileNameInput(character));
+            assert!(result.is_ok());
+        }
+        let result = controller.handle_action(Action::DeleteProfileNameChar);
+        assert!(result.is_ok());
+        let result = controller.handle_action(Action::ProfileNameInput('a'));
+        assert!(result.is_ok());
+        let result = controller.handle_action(Action::PickProfile);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_page(), &Page::Menu);
+        let ana = controller.state.get_profile().clone().unwrap();
+        assert_eq!(ana.name, "ana");
+        // the picker opens on the picked profile, guests come first
+        let result = controller.handle_action(Action::ChangePage(Page::Profiles));
+        assert!(result.is_ok());
+        let ana_index = controller.state.get_profile_index();
+        assert_eq!(controller.state.get_profiles()[ana_index as usize - 1], ana);
+        let result = controller.handle_action(Action::ProfileAction(-ana_index));
+        assert!(result.is_ok());
+        let result = controller.handle_action(Action::PickProfile);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_profile(), &None);
+        controller.state.set_profile(Some(ana.clone()));
+        let result = controller.handle_action(Action::PostRecord);
+        assert!(result.is_ok());
+        let result = controller.handle_action(Action::GetRecords);
+        assert!(result.is_ok());
+        assert!(controller
+            .state
+            .get_records()
+            .iter()
+            .all(|record| record.player_id == Some(ana.id)));
+        assert!(controller
+            .state
+            .get_player_bests()
+            .iter()
+            .any(|(name, _)| *name == ana.name));
+
+        // HOT SEAT TEST
+        let result = controller.handle_action(Action::ChangeHotSeat(1));
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_hot_seat_size(), 2);
+        let result = controller.handle_action(Action::ChangeHotSeat(-2));
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_hot_seat_size(), MAX_HOT_SEAT_PLAYERS);
+        controller.state.set_hot_seat_size(2);
+        controller.state.set_page(Page::Menu);
+        controller.state.set_menu_index(0);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(action, Action::StartHotSeat);
+        controller.state.set_mode(Mode::Words(10));
+        let result = controller.handle_action(action);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_page(), &Page::Profiles);
+        let mut paragraphs = Vec::new();
+        for name in ["bo", "ana"] {
+            // a player changing the mode between turns doesn't change the text
+            controller.state.set_mode(Mode::Time(15));
+            for character in name.chars() {
+                let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Char(character)));
+                let result = controller.handle_action(action);
+                assert!(result.is_ok());
+            }
+            let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Enter));
+            assert_eq!(action, Action::PickProfile);
+            let result = controller.handle_action(action);
+            assert!(result.is_ok());
+            assert_eq!(controller.state.get_page(), &Page::CountDown);
+            assert_eq!(controller.state.get_mode(), Mode::Words(10));
+            paragraphs.push(controller.state.get_paragraph().clone());
+            controller.state.set_page(Page::Game);
+            let result = controller.handle_action(Action::ChangePage(Page::GameResult));
+            assert!(result.is_ok());
+            let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Enter));
+            let result = controller.handle_action(action);
+            assert!(result.is_ok());
+        }
+        assert_eq!(paragraphs[0], paragraphs[1]);
+        assert_eq!(controller.state.get_page(), &Page::Leaderboard);
+        let hot_seat = controller.state.get_hot_seat().clone().unwrap();
+        assert!(hot_seat.is_finished());
+        assert_eq!(hot_seat.results[0].name, "bo");
+        assert_eq!(hot_seat.results[1].name, "ana");
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Esc));
+        assert_eq!(action, Action::EndHotSeat);
+        let result = controller.handle_action(action);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_hot_seat(), &None);
+        assert_eq!(controller.state.get_page(), &Page::Menu);
+
+        // backing out in the middle of a turn ends the tournament
+        let result = controller.handle_action(Action::StartHotSeat);
+        assert!(result.is_ok());
+        let result = controller.handle_action(Action::PickProfile);
+        assert!(result.is_ok());
+        controller.state.set_page(Page::Game);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Esc));
+        assert_eq!(action, Action::EndHotSeat);
+        let result = controller.handle_action(action);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_hot_seat(), &None);
+        assert_eq!(controller.state.get_page(), &Page::Menu);
+
+        // and so does backing out of a result before the last turn
+        let result = controller.handle_action(Action::StartHotSeat);
+        assert!(result.is_ok());
+        let result = controller.handle_action(Action::PickProfile);
+        assert!(result.is_ok());
+        controller.state.set_page(Page::Game);
+        let result = controller.handle_action(Action::ChangePage(Page::GameResult));
+        assert!(result.is_ok());
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Esc));
+        assert_eq!(action, Action::EndHotSeat);
+        let result = controller.handle_action(action);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_hot_seat(), &None);
+
+        // the result of the last turn leads to the leaderboard
+        controller.state.set_hot_seat(Some(hot_seat));
         controller.state.set_page(Page::GameResult);
-        let action = controller.handle_key_stroke(KeyCode::Enter);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Esc));
+        assert_eq!(action, Action::ChangePage(Page::Leaderboard));
+        controller.state.set_hot_seat(None);
+        controller.state.set_page(Page::Menu);
+        controller.state.set_hot_seat_size(0);
+        controller.state.set_profile(None);
+
+        // PUNCTUATION AND NUMBERS TEST
+        let result = controller.handle_action(Action::TogglePunctuation);
+        assert!(result.is_ok());
+        assert!(controller.state.get_punctuation());
+        let result = controller.handle_action(Action::ToggleNumbers);
+        assert!(result.is_ok());
+        assert!(controller.state.get_numbers());
+        controller.state.set_mode(Mode::Words(100));
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(result.is_ok());
+        let paragraph = controller.state.get_paragraph();
+        assert_eq!(paragraph.split(' ').count(), 100);
+        assert!(paragraph.starts_with(|character: char| !character.is_lowercase()));
+        assert!(paragraph.ends_with('.'));
+        assert!(paragraph
+            .split(' ')
+            .any(|word| word.parse::<u32>().is_ok() || word.ends_with(',')));
+        let result = controller.handle_action(Action::TogglePunctuation);
+        assert!(result.is_ok());
+        let result = controller.handle_action(Action::ToggleNumbers);
+        assert!(result.is_ok());
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(result.is_ok());
+        assert!(controller
+            .state
+            .get_paragraph()
+            .chars()
+            .all(|character| !character.is_ascii_digit() && character != ','));
+
+        // EMPTY ACTION TEST
+        let action = Action::Empty;
+        let result = controller.handle_action(action);
+        assert!(result.is_ok());
+
+        // EXIT TEST
+        let action = Action::Exit;
+        let result = controller.handle_action(action);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_is_running(), false);
+    }
+
+    #[test]
+    fn test_records_table() {
+        let mut controller = Controller::with_store(MemoryStore::new());
+        for index in 0..25 {
+            let record = Record {
+                wpm: (index * 7) % 25 + 40,
+                date: format!("2025-01-{:02} 10:00:00", index + 1),
+                mode: "words 25".to_string(),
+                ..Record::default()
+            };
+            let result = controller.store.create_record(&record);
+            assert!(result.is_ok());
+        }
+
+        // newest first, one page at a time
+        let result = controller.handle_action(Action::ChangePage(Page::Records));
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_record_count(), 25);
+        assert_eq!(controller.state.get_records().len(), RECORDS_PAGE_SIZE);
+        assert_eq!(
+            controller.state.get_records()[0].date,
+            "2025-01-25 10:00:00"
+        );
+        // 64 wpm is the fastest
+        assert_eq!(controller.state.get_record_best(), Some(8));
+
+        // SCROLL TEST
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Down));
+        assert_eq!(action, Action::RecordAction(1));
+        let result = controller.handle_action(action);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_record_index(), 1);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::PageDown));
+        assert_eq!(action, Action::RecordAction(RECORDS_PAGE_SIZE as i32));
+        let result = controller.handle_action(action);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_record_index(), 11);
+        assert_eq!(
+            controller.state.get_records()[0].date,
+            "2025-01-15 10:00:00"
+        );
+        let result = controller.handle_action(Action::RecordAction(100));
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_record_index(), 24);
+        assert_eq!(controller.state.get_records().len(), 5);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::PageUp));
+        assert_eq!(action, Action::RecordAction(-(RECORDS_PAGE_SIZE as i32)));
+        let result = controller.handle_action(Action::RecordAction(-100));
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_record_index(), 0);
+
+        // SORT TEST
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Right));
+        assert_eq!(action, Action::SortRecords(1));
+        let result = controller.handle_action(Action::SortRecords(2));
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_record_column(), RecordColumn::Wpm);
+        assert_eq!(controller.state.get_records()[0].wpm, 64);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(action, Action::ReverseRecords);
+        let result = controller.handle_action(action);
+        assert!(result.is_ok());
+        assert!(controller.state.get_record_ascending());
+        assert_eq!(controller.state.get_records()[0].wpm, 40);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Left));
+        assert_eq!(action, Action::SortRecords(-1));
+        let result = controller.handle_action(Action::SortRecords(-3));
+        assert!(result.is_ok());
+        assert_eq!(
+            controller.state.get_record_column(),
+            RecordColumn::Consistency
+        );
+        assert!(!controller.state.get_record_ascending());
+    }
+
+    #[test]
+    fn test_handle_key_stroke() {
+        // ESC KEY -- MENU PAGE TEST
+        let mut controller = Controller::new();
+        controller.state.set_page(Page::Menu);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Esc));
+        assert_eq!(action, Action::Exit);
+
+        // ESC KEY -- GAME PAGE TEST
+        controller.state.set_page(Page::Game);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Esc));
+        assert_eq!(action, Action::ChangePage(Page::Menu));
+
+        // ENTER KEY -- MENU PAGE TEST
+        controller.state.set_page(Page::Menu);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(action, Action::ChangePage(Page::CountDown));
+        controller.state.set_menu_index(1);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(action, Action::ChangeMode(1));
+        controller.state.set_menu_index(2);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(action, Action::ChangeWordList(1));
+        controller.state.set_menu_index(3);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(action, Action::TogglePunctuation);
+        controller.state.set_menu_index(4);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(action, Action::ToggleNumbers);
+        controller.state.set_menu_index(5);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(action, Action::ToggleGhost);
+        controller.state.set_menu_index(6);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(action, Action::ChangeHotSeat(1));
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Left));
+        assert_eq!(action, Action::ChangeHotSeat(-1));
+        controller.state.set_menu_index(7);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(action, Action::ChangePage(Page::Profiles));
+        controller.state.set_menu_index(8);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(action, Action::ChangePage(Page::Records));
+
+        // LEFT / RIGHT KEY -- MENU PAGE TEST
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Left));
+        assert_eq!(action, Action::Empty);
+        controller.state.set_menu_index(2);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Left));
+        assert_eq!(action, Action::ChangeWordList(-1));
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Right));
+        assert_eq!(action, Action::ChangeWordList(1));
+        controller.state.set_menu_index(3);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Left));
+        assert_eq!(action, Action::TogglePunctuation);
+        controller.state.set_menu_index(1);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Left));
+        assert_eq!(action, Action::ChangeMode(-1));
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Right));
+        assert_eq!(action, Action::ChangeMode(1));
+
+        // ENTER KEY -- GAME RESULT PAGE TEST
+        controller.state.set_page(Page::GameResult);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Enter));
         assert_eq!(action, Action::ChangePage(Page::Menu));
 
         // ENTER KEY -- OTHER PAGE TEST
         controller.state.set_page(Page::Game);
-        let action = controller.handle_key_stroke(KeyCode::Enter);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(action, Action::Empty);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Tab));
         assert_eq!(action, Action::Empty);
 
+        // ENTER / TAB KEY -- CODE MODE TEST
+        let mode = controller.state.get_mode();
+        controller.state.set_mode(Mode::Code(Language::Python));
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(action, Action::CharInput('\n'));
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Tab));
+        assert_eq!(action, Action::CharInput('\t'));
+        controller.state.set_mode(mode);
+
         // CHAR -- GAME PAGE TEST
         controller.state.set_page(Page::Game);
-        let action = controller.handle_key_stroke(KeyCode::Char('T'));
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Char('T')));
         assert_eq!(action, Action::CharInput('T'));
 
         // CHAR -- OTHER PAGE TEST
         controller.state.set_page(Page::Menu);
-        let action = controller.handle_key_stroke(KeyCode::Char('T'));
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Char('T')));
         assert_eq!(action, Action::Empty);
 
+        // BOUND CHAR -- PROFILES PAGE TEST
+        controller.state.set_config(Config {
+            key_bindings: KeyBindings {
+                back: "q".parse().unwrap(),
+                ..KeyBindings::default()
+            },
+            ..Config::default()
+        });
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Char('q')));
+        assert_eq!(action, Action::Exit);
+        controller.state.set_page(Page::Profiles);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Char('q')));
+        assert_eq!(action, Action::ProfileNameInput('q'));
+        controller.state.set_config(Config::default());
+        controller.state.set_page(Page::Menu);
+
         // DOWN KEY  -- MENU PAGE TEST
         controller.state.set_page(Page::Menu);
-        let action = controller.handle_key_stroke(KeyCode::Down);
-        assert_eq!(action, Action::MenuAction);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Down));
+        assert_eq!(action, Action::MenuAction(1));
 
         // DOWN KEY -- OTHER PAGE TEST
         controller.state.set_page(Page::Game);
-        let action = controller.handle_key_stroke(KeyCode::Down);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Down));
         assert_eq!(action, Action::Empty);
 
         // UP KEY  -- MENU PAGE TEST
         controller.state.set_page(Page::Menu);
-        let action = controller.handle_key_stroke(KeyCode::Up);
-        assert_eq!(action, Action::MenuAction);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Up));
+        assert_eq!(action, Action::MenuAction(-1));
 
         // UP KEY -- OTHER PAGE TEST
         controller.state.set_page(Page::Game);
-        let action = controller.handle_key_stroke(KeyCode::Up);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Up));
+        assert_eq!(action, Action::Empty);
+
+        // CTRL + CHAR -- GAME PAGE TEST
+        let action =
+            controller.handle_key_stroke(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
         assert_eq!(action, Action::Empty);
 
+        // BACKSPACE KEY -- GAME PAGE TEST
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Backspace));
+        assert_eq!(action, Action::DeleteChar);
+
+        // CTRL + BACKSPACE KEY -- GAME PAGE TEST
+        let action =
+            controller.handle_key_stroke(KeyEvent::new(KeyCode::Backspace, KeyModifiers::CONTROL));
+        assert_eq!(action, Action::DeleteWord);
+        let action =
+            controller.handle_key_stroke(KeyEvent::new(KeyCode::Char('h'), KeyModifiers::CONTROL));
+        assert_eq!(action, Action::DeleteWord);
+        let action =
+            controller.handle_key_stroke(KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL));
+        assert_eq!(action, Action::DeleteWord);
+
         // BACKSPACE KEY -- OTHER PAGE TEST
-        let action = controller.handle_key_stroke(KeyCode::Backspace);
+        controller.state.set_page(Page::Menu);
+        let action = controller.handle_key_stroke(KeyEvent::from(KeyCode::Backspace));
+        assert_eq!(action, Action::Empty);
+        let action =
+            controller.handle_key_stroke(KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL));
         assert_eq!(action, Action::Empty);
     }
 
-    // god knows why this test is failing on github actions
-    // #[test]
-    // fn test_handle_events() {
-    //     let mut controller = Controller::new();
-    //     let result = controller.handle_events();
-    //     assert!(result.is_ok());
-    // }
+    #[test]
+    fn test_load_config() {
+        let mut controller = Controller::new();
+        let result = controller.load_config(&get_test_db_path().join("config"));
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_mode(), Mode::Words(25));
+        assert_eq!(controller.state.get_config().countdown, 1);
+        assert_eq!(controller.state.get_word_list(), "alpha\nbeta\ngamma\n");
+        assert_eq!(controller.state.get_word_list_name(), "word_list_mock");
+
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(result.is_ok());
+        // the three words are repeated to fill the mode
+        assert_eq!(controller.state.get_paragraph().split(' ').count(), 25);
+
+        let action =
+            controller.handle_key_stroke(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL));
+        assert_eq!(action, Action::ChangePage(Page::Menu));
+
+        // a missing config keeps the defaults
+        let mut controller = Controller::new();
+        let result = controller.load_config(&get_test_db_path());
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_config(), &Config::default());
+
+        let mut controller = Controller::new();
+        let result = controller.load_config(&get_test_db_path().join("invalid_config"));
+        assert!(matches!(result, Err(ControllerError::ConfigError(_))));
+        assert_eq!(controller.state.get_mode(), Mode::Time(60));
+    }
+
+    #[test]
+    fn test_apply_args() {
+        let mut controller = Controller::new();
+        let cli = Cli {
+            words: Some(3),
+            wordlist: Some(get_test_db_path().join("config").join("word_list_mock.txt")),
+            seed: Some(7),
+            ..Cli::default()
+        };
+        let result = controller.apply_args(&cli);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_mode(), Mode::Words(3));
+        assert_eq!(controller.state.get_seed(), Some(7));
+
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(result.is_ok());
+        let paragraph = controller.state.get_paragraph().clone();
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_paragraph(), &paragraph);
+        assert_eq!(paragraph.split(' ').count(), 3);
+
+        let cli = Cli {
+            wordlist: Some(get_test_db_path().join("idk.txt")),
+            ..Cli::default()
+        };
+        let result = controller.apply_args(&cli);
+        assert!(matches!(result, Err(ControllerError::FileSystemError(_))));
+    }
+
+    #[test]
+    fn test_load_word_lists() {
+        let mut controller = Controller::new();
+        let config_path = get_test_db_path().join("config");
+        let result = controller.load_word_lists(&config_path);
+        assert!(matches!(
+            result,
+            Err(ControllerError::FileSystemError(
+                FileSystemError::InvalidWordList(_, _)
+            ))
+        ));
+        let names = controller
+            .state
+            .get_word_lists()
+            .iter()
+            .map(|word_list| word_list.name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(names, vec!["products", "rust"]);
+
+        // CHANGE WORD LIST TEST
+        let result = controller.handle_action(Action::ChangeWordList(1));
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_word_list_name(), "products");
+        let result = controller.handle_action(Action::ChangeWordList(1));
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_word_list_name(), "rust");
+        let result = controller.handle_action(Action::ChangeWordList(1));
+        assert!(result.is_ok());
+        assert_eq!(
+            controller.state.get_word_list_name(),
+            DEFAULT_WORD_LIST_NAME
+        );
+        let result = controller.handle_action(Action::ChangeWordList(-1));
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_word_list_name(), "rust");
+
+        // custom lists keep their casing
+        controller.state.set_mode(Mode::Words(10));
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(result.is_ok());
+        assert!(controller.state.get_paragraph().contains("Self"));
+        // the 10 word list still fills a minute of typing
+        controller.state.set_mode(Mode::Time(60));
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_paragraph().split(' ').count(), 240);
+
+        // SELECT WORD LIST TEST
+        let result = controller.select_word_list("products.txt", &config_path);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_word_list_name(), "products");
+        let result = controller.select_word_list("default", &config_path);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_word_list(), WORD_LIST);
+        let result = controller.select_word_list("word_list_mock.txt", &config_path);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_word_list_name(), "word_list_mock");
+        let result = controller.select_word_list("idk", &config_path);
+        assert!(result.is_err());
+
+        // a missing folder has no lists
+        let mut controller = Controller::new();
+        let result = controller.load_word_lists(&get_test_db_path());
+        assert!(result.is_ok());
+        assert!(controller.state.get_word_lists().is_empty());
+    }
+
+    #[test]
+    fn test_ghost_paragraph_settings() {
+        let mut controller = Controller::with_store(MemoryStore::new());
+        let config_path = get_test_db_path().join("config");
+        // the folder has a broken list next to the valid ones
+        let result = controller.load_word_lists(&config_path);
+        assert!(result.is_err());
+        let result = controller.select_word_list("rust", &config_path);
+        assert!(result.is_ok());
+        controller.state.set_mode(Mode::Words(10));
+        controller.state.set_punctuation(true);
+        controller.state.set_seed(Some(5));
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(result.is_ok());
+        let paragraph = controller.state.get_paragraph().clone();
+        let result = controller.handle_action(Action::PostRecord);
+        assert!(result.is_ok());
+        let record = controller.store.get_records().unwrap()[0].clone();
+        assert_eq!(record.word_list, Some("rust".to_string()));
+        assert!(record.punctuation);
+        assert!(!record.numbers);
+
+        // racing the record brings back the list and toggles its text was built with
+        controller.state.set_word_list(None);
+        controller.state.set_punctuation(false);
+        controller.state.set_numbers(true);
+        controller.state.set_seed(None);
+        controller
+            .state
+            .set_ghost_target(Some(GhostTarget::Record(record.id)));
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_paragraph(), &paragraph);
+        assert_eq!(controller.state.get_word_list_name(), "rust");
+        assert!(controller.state.get_punctuation());
+        assert!(!controller.state.get_numbers());
+
+        let record = Record {
+            mode: "words 10".to_string(),
+            seed: Some(1),
+            word_list: Some("greek".to_string()),
+            ..Record::default()
+        };
+        let record_id = controller.store.create_record(&record).unwrap() as i32;
+        controller
+            .state
+            .set_ghost_target(Some(GhostTarget::Record(record_id)));
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(matches!(
+            result,
+            Err(ControllerError::MissingGhostWordList(id, name)) if id == record_id && name == "greek"
+        ));
+
+        // the daily text doesn't depend on the settings
+        controller.state.set_ghost_target(None);
+        controller.state.set_mode(Mode::Daily);
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(result.is_ok());
+        let result = controller.handle_action(Action::PostRecord);
+        assert!(result.is_ok());
+        let record = controller.store.get_records().unwrap().pop().unwrap();
+        assert_eq!(record.word_list, None);
+        assert!(!record.punctuation);
+    }
+
+    #[test]
+    fn test_sample_progress() {
+        let clock = ManualClock::new(100_000);
+        let mut controller = Controller::with_clock(SharedClock::new(clock.clone()));
+        controller.state.set_mode(Mode::Time(15));
+        controller.state.set_paragraph("some words".to_string());
+        controller.state.set_page(Page::Game);
+        controller.state.set_reference_timestamp(None);
+        clock.advance(500);
+        for character in "some".chars() {
+            let result = controller.handle_action(Action::CharInput(character));
+            assert!(result.is_ok());
+        }
+
+        // no full second has passed yet
+        controller.sample_progress();
+        assert!(controller.state.get_samples().is_empty());
+
+        clock.advance(3000);
+        controller.sample_progress();
+        let samples = controller.state.get_samples();
+        assert_eq!(samples.len(), 3);
+        assert_eq!(samples[0].second, 1);
+        assert_eq!(samples[0].wpm, 48);
+        assert_eq!(samples[2].second, 3);
+        assert_eq!(samples[2].wpm, 16);
+
+        // timed tests never sample past their duration
+        clock.advance(16500);
+        controller.sample_progress();
+        assert_eq!(controller.state.get_samples().len(), 15);
+    }
+
+    #[test]
+    fn test_daily_seed_from_clock() {
+        let noon = Local.with_ymd_and_hms(2024, 1, 31, 12, 0, 0).unwrap();
+        let clock = ManualClock::new(noon.timestamp_millis());
+        let mut controller = Controller::with_clock(SharedClock::new(clock.clone()));
+        controller.state.set_mode(Mode::Daily);
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_paragraph_seed(), Some(20240131));
+        let paragraph = controller.state.get_paragraph().clone();
+
+        // the next day has another text
+        clock.advance(24 * 60 * 60 * 1000);
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_paragraph_seed(), Some(20240201));
+        assert_ne!(controller.state.get_paragraph(), &paragraph);
+    }
+
+    #[test]
+    fn test_tick() {
+        let clock = ManualClock::new(100_000);
+        let mut controller = Controller {
+            store: Box::new(MemoryStore::new()),
+            ..Controller::with_clock(SharedClock::new(clock.clone()))
+        };
+        controller.state.set_mode(Mode::Time(15));
+
+        // nothing runs before the countdown starts
+        let result = controller.tick();
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_timer(), 100_000);
+        assert_eq!(controller.state.get_page(), &Page::Menu);
+
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(result.is_ok());
+        let countdown_millis = controller.state.get_config().countdown * 1000;
+        clock.advance(countdown_millis - 1);
+        let result = controller.tick();
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_page(), &Page::CountDown);
+        clock.advance(1);
+        let result = controller.tick();
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_page(), &Page::Game);
+
+        // 10 correct characters in 1.5 seconds
+        let paragraph = controller.state.get_paragraph().clone();
+        let typed = paragraph.chars().take(10).collect::<String>();
+        for character in typed.chars() {
+            let result = controller.handle_action(Action::CharInput(character));
+            assert!(result.is_ok());
+        }
+        clock.advance(1500);
+        assert_eq!(
+            calculate_word_speed(
+                controller.state.get_char_count(),
+                controller.state.get_elapsed_millis()
+            ),
+            80
+        );
+
+        // the test ends on the dot, not a second later
+        clock.advance(13_499);
+        let result = controller.tick();
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_page(), &Page::Game);
+        clock.advance(1);
+        let result = controller.tick();
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_page(), &Page::GameResult);
+        assert_eq!(controller.state.get_duration(), 15_000);
+        assert_eq!(controller.state.get_samples().len(), 15);
+        assert_eq!(controller.store.get_records().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_handle_code_input() {
+        let mut controller = Controller::new();
+        controller.state.set_page(Page::Game);
+        controller
+            .state
+            .set_paragraph("if x:\n    y()\n\nz end".to_string());
+
+        for character in "if x:".chars() {
+            let result = controller.handle_action(Action::CharInput(character));
+            assert!(result.is_ok());
+        }
+        // enter fills in the indentation, spaces after it are ignored
+        for character in "\n  ".chars() {
+            let result = controller.handle_action(Action::CharInput(character));
+            assert!(result.is_ok());
+        }
+        assert_eq!(controller.state.get_input(), "if x:\n    ");
+        assert_eq!(controller.state.get_position(), 10);
+        assert_eq!(controller.state.get_word_count(), 2);
+        assert_eq!(controller.state.get_correct_count(), 6);
+        let keystrokes = controller.state.get_keystrokes();
+        assert_eq!(keystrokes.last().unwrap().expected, Some("\n".to_string()));
+
+        // backspace removes the line break with its indentation
+        let result = controller.handle_action(Action::DeleteChar);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_input(), "if x:");
+
+        // without skipping the indentation is typed, tab fills in the rest of it
+        controller.state.set_config(Config {
+            skip_indentation: false,
+            ..Config::default()
+        });
+        for character in "\n \t\t".chars() {
+            let result = controller.handle_action(Action::CharInput(character));
+            assert!(result.is_ok());
+        }
+        assert_eq!(controller.state.get_input(), "if x:\n    ");
+        assert_eq!(controller.state.get_correct_count(), 9);
+
+        // blank lines take a line break each
+        for character in "y()\n\n".chars() {
+            let result = controller.handle_action(Action::CharInput(character));
+            assert!(result.is_ok());
+        }
+        assert_eq!(controller.state.get_input(), "if x:\n    y()\n\n");
+
+        // a line break where a space was expected is a mistake
+        for character in "z\n".chars() {
+            let result = controller.handle_action(Action::CharInput(character));
+            assert!(result.is_ok());
+        }
+        assert_eq!(controller.state.get_incorrect_count(), 1);
+        let result = controller.handle_action(Action::CharInput(' '));
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_input(), "if x:\n    y()\n\nz\n");
+    }
+
+    /// Polls the race until the condition holds, the server answers on other threads.
+    fn poll_race_until<F>(controller: &mut Controller, is_done: F)
+    where
+        F: Fn(&State) -> bool,
+    {
+        for _ in 0..500 {
+            let result = controller.poll_race();
+            assert!(result.is_ok());
+            if is_done(&controller.state) {
+                return;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        panic!("the race never got there");
+    }
+
+    #[test]
+    fn test_race() {
+        let mut host = Controller::new();
+        let port = host.host_race(0, "ana").unwrap();
+        poll_race_until(&mut host, |state| state.get_race_player_id().is_some());
+        assert!(host.state.get_is_race_host());
+
+        let mut guest = Controller::new();
+        let result = guest.join_race(("127.0.0.1", port), "bo");
+        assert!(result.is_ok());
+        poll_race_until(&mut guest, |state| state.get_race_players().len() == 2);
+        assert!(!guest.state.get_is_race_host());
+        poll_race_until(&mut host, |state| state.get_race_players().len() == 2);
+
+        // only the host starts, with its own mode
+        assert_eq!(
+            guest.handle_key_stroke(KeyEvent::from(KeyCode::Enter)),
+            Action::Empty
+        );
+        host.state.set_mode(Mode::Words(10));
+        host.state.set_punctuation(true);
+        let action = host.handle_key_stroke(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(action, Action::StartRace);
+        let result = host.handle_action(action);
+        assert!(result.is_ok());
+        poll_race_until(&mut host, |state| *state.get_page() == Page::CountDown);
+        poll_race_until(&mut guest, |state| *state.get_page() == Page::CountDown);
+        assert_eq!(guest.state.get_mode(), Mode::Words(10));
+        assert_eq!(
+            host.state.get_paragraph_seed(),
+            guest.state.get_paragraph_seed()
+        );
+        // the text doesn't depend on the settings of the players
+        assert_eq!(host.state.get_paragraph(), guest.state.get_paragraph());
+        assert_eq!(split_words(guest.state.get_paragraph()).len(), 10);
+
+        // progress shows up for the other players
+        guest.state.set_page(Page::Game);
+        let first_word = split_words(guest.state.get_paragraph())[0].to_string();
+        for character in first_word.chars() {
+            let result = guest.handle_action(Action::CharInput(character));
+            assert!(result.is_ok());
+        }
+        let result = guest.poll_race();
+        assert!(result.is_ok());
+        let position = guest.state.get_position() as usize;
+        poll_race_until(&mut host, |state| {
+            state
+                .get_race_players()
+                .iter()
+                .any(|player| player.name == "bo" && player.progress.position == position)
+        });
+
+        // the guest carries on alone once the host leaves
+        drop(host);
+        poll_race_until(&mut guest, |state| state.get_race_player_id().is_none());
+        assert_eq!(guest.state.get_page(), &Page::Menu);
+        assert!(guest.state.get_race_players().is_empty());
+        assert!(guest.race.is_none());
+    }
+
+    #[test]
+    fn test_handle_multi_byte_input() {
+        let mut controller = Controller::new();
+        controller.state.set_page(Page::Game);
+        controller
+            .state
+            .set_paragraph("héllo wörld 日本語 ne\u{301} end".to_string());
+
+        for character in "hél".chars() {
+            let result = controller.handle_action(Action::CharInput(character));
+            assert!(result.is_ok());
+        }
+        assert_eq!(controller.state.get_position(), 3);
+        assert_eq!(controller.state.get_correct_count(), 3);
+
+        let result = controller.handle_action(Action::CharInput(' '));
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_missed_count(), 2);
+        assert_eq!(controller.state.get_position(), 6);
+
+        for character in "wërld 日本".chars() {
+            let result = controller.handle_action(Action::CharInput(character));
+            assert!(result.is_ok());
+        }
+        assert_eq!(controller.state.get_incorrect_count(), 1);
+        assert_eq!(controller.state.get_position(), 14);
+        let keystrokes = controller.state.get_keystrokes();
+        assert_eq!(keystrokes.last().unwrap().expected, Some("本".to_string()));
+
+        let result = controller.handle_action(Action::DeleteChar);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_input(), "hél wërld 日");
+        assert_eq!(controller.state.get_position(), 13);
+
+        // a grapheme typed as a letter and a combining accent
+        for character in "本語 ne\u{301}".chars() {
+            let result = controller.handle_action(Action::CharInput(character));
+            assert!(result.is_ok());
+        }
+        assert_eq!(controller.state.get_position(), 18);
+        assert_eq!(controller.state.get_incorrect_count(), 1);
+        let keystrokes = controller.state.get_keystrokes();
+        let accent = keystrokes.last().unwrap();
+        assert_eq!(accent.expected, Some("e\u{301}".to_string()));
+        assert!(accent.is_correct);
+    }
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::from(code))
+    }
+
+    #[test]
+    fn test_handle_events() {
+        let clock = ManualClock::new(100_000);
+        let mut controller = Controller::with_clock(SharedClock::new(clock.clone()));
+        controller.state.set_page(Page::Game);
+        controller.state.set_paragraph("abc".to_string());
+        let script = vec![(0, key(KeyCode::Char('a'))), (100, key(KeyCode::Char('b')))];
+        let mut events = ScriptedEvents::new(clock.clone(), script);
+
+        let result = controller.handle_events(&mut events);
+        assert!(result.is_ok());
+        assert_eq!(controller.state.get_input(), "a");
+        while controller.state.get_input() == "a" {
+            let result = controller.handle_events(&mut events);
+            assert!(result.is_ok());
+        }
+        assert_eq!(controller.state.get_input(), "ab");
+        assert_eq!(clock.now_millis(), 100_100);
+    }
+
+    /// Runs the app on a test terminal with the test config folder until the script
+    /// makes it exit, and returns the records the session saved with their keystrokes.
+    fn run_session(cli: &Cli, script: Vec<(i64, Event)>) -> Vec<(Record, Vec<Keystroke>)> {
+        let clock = ManualClock::new(1_000_000);
+        let mut controller = Controller::with_clock(SharedClock::new(clock.clone()));
+        let mut events = ScriptedEvents::new(clock, script);
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        let result = controller.run_in(
+            &mut terminal,
+            &mut events,
+            cli,
+            get_test_db_path().join("config"),
+        );
+        assert!(result.is_ok());
+        assert!(!controller.state.get_is_running());
+
+        let (db_folder, db_name) = cli.get_db_location(get_test_db_path());
+        let mut client = Client::default();
+        let result = client.open_connection(db_folder.clone(), &db_name);
+        assert!(result.is_ok());
+        let records = client
+            .get_records()
+            .unwrap()
+            .into_iter()
+            .map(|record| {
+                let keystrokes = client.get_keystrokes(record.id).unwrap();
+                (record, keystrokes)
+            })
+            .collect();
+        let result = client.close_connection();
+        assert!(result.is_ok());
+        let result = std::fs::remove_file(db_folder.join(db_name));
+        assert!(result.is_ok());
+        records
+    }
+
+    /// Builds the text a run with the flags gets, the seed makes it the same every time.
+    fn get_session_paragraph(cli: &Cli) -> String {
+        let mut controller = Controller::new();
+        let result = controller.load_config(&get_test_db_path().join("config"));
+        assert!(result.is_ok());
+        let result = controller.apply_args(cli);
+        assert!(result.is_ok());
+        let result = controller.handle_action(Action::ChangePage(Page::CountDown));
+        assert!(result.is_ok());
+        controller.state.get_paragraph().clone()
+    }
+
+    #[test]
+    fn test_run_words_session() {
+        let cli = Cli {
+            words: Some(10),
+            seed: Some(7),
+            db: Some(get_test_db_path().join(format!("run_words_{TEST_DB_NAME}"))),
+            ..Cli::default()
+        };
+        let paragraph = get_session_paragraph(&cli);
+        // the countdown of the test config takes a second, a key every 100ms after it
+        let mut script = vec![(0, key(KeyCode::Enter))];
+        script.extend(paragraph.chars().enumerate().map(|(index, character)| {
+            let delay = if index == 0 { 1100 } else { 100 };
+            (delay, key(KeyCode::Char(character)))
+        }));
+
+        let records = run_session(&cli, script);
+        assert_eq!(records.len(), 1);
+        let (record, keystrokes) = &records[0];
+        assert_eq!(record.mode, "words 10");
+        assert_eq!(record.seed, Some(7));
+        assert_eq!(record.accuracy, 100.0);
+        assert_eq!(keystrokes.len(), paragraph.chars().count());
+        let first_timestamp = keystrokes.first().unwrap().timestamp;
+        let last_timestamp = keystrokes.last().unwrap().timestamp;
+        assert_eq!(
+            last_timestamp - first_timestamp,
+            100 * (paragraph.chars().count() as i64 - 1)
+        );
+        // the test ends with its last key
+        assert_eq!(
+            record.wpm,
+            calculate_word_speed(paragraph.chars().count() as u64, last_timestamp)
+        );
+    }
+
+    #[test]
+    fn test_run_time_session() {
+        let cli = Cli {
+            time: Some(15),
+            seed: Some(7),
+            db: Some(get_test_db_path().join(format!("run_time_{TEST_DB_NAME}"))),
+            ..Cli::default()
+        };
+        let paragraph = get_session_paragraph(&cli);
+        let first_word = split_words(&paragraph)[0].to_string();
+        let mut script = vec![(0, key(KeyCode::Enter))];
+        script.extend(
+            first_word
+                .chars()
+                .map(|character| (1100, key(KeyCode::Char(character)))),
+        );
+        // long after the timer ran out
+        script.push((20_000, key(KeyCode::Esc)));
+
+        let records = run_session(&cli, script);
+        assert_eq!(records.len(), 1);
+        let (record, keystrokes) = &records[0];
+        assert_eq!(record.mode, "time 15");
+        assert_eq!(keystrokes.len(), first_word.chars().count());
+        // timed tests end on the dot however late the timer is checked
+        assert_eq!(
+            record.wpm,
+            calculate_word_speed(first_word.chars().count() as u64, 15_000)
+        );
+    }
+
+    #[test]
+    fn test_run_no_save_session() {
+        let db_path = get_test_db_path().join(format!("no_save_{TEST_DB_NAME}"));
+        let cli = Cli {
+            words: Some(10),
+            seed: Some(7),
+            db: Some(db_path.clone()),
+            no_save: true,
+            ..Cli::default()
+        };
+        let paragraph = get_session_paragraph(&cli);
+        let mut script = vec![(0, key(KeyCode::Enter))];
+        script.extend(
+            paragraph
+                .chars()
+                .map(|character| (1100, key(KeyCode::Char(character)))),
+        );
+
+        let clock = ManualClock::new(1_000_000);
+        let mut controller = Controller::with_clock(SharedClock::new(clock.clone()));
+        let mut events = ScriptedEvents::new(clock, script);
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        let result = controller.run_in(
+            &mut terminal,
+            &mut events,
+            &cli,
+            get_test_db_path().join("config"),
+        );
+        assert!(result.is_ok());
+        // the record only lives as long as the session
+        assert_eq!(controller.store.get_records().unwrap().len(), 1);
+        assert!(!db_path.exists());
+    }
 
     #[test]
     fn test_init_controller() {
         let mut controller = Controller::new();
-        let db_name = format!("controller_{TEST_DB_NAME}");
+        let db_name = format!("init_{TEST_DB_NAME}");
         let result = controller.init_controller(get_test_db_path(), db_name.as_str());
         assert!(result.is_ok());
-        let result = controller.client.drop_records_table();
+        assert!(controller.state.get_is_running());
+        let result = controller.exit();
+        assert!(result.is_ok());
+        let result = std::fs::remove_file(get_test_db_path().join(db_name));
         assert!(result.is_ok());
     }
 }
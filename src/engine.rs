@@ -0,0 +1,514 @@
+//! Scores typing against a target text without any terminal or storage attached. A
+//! `TypingSession` takes keystrokes stamped with the milliseconds since the start and keeps
+//! the caret, the keystroke counters and the per second samples the results are built from.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::constants::{BACKSPACE_CHAR, DELETE_WORD_CHAR};
+use crate::keystroke::Keystroke;
+use crate::sample::Sample;
+use crate::util::{
+    calculate_accuracy, calculate_burst_speed, calculate_char_speed, calculate_consistency,
+    calculate_word_speed, count_correct_chars, get_caret_position, get_current_word, get_sample,
+    is_paragraph_finished, is_separator, split_separators, split_words,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypingSession {
+    pub paragraph: String,
+    pub input: String,
+    /// Enter fills in the indentation of the next line, otherwise tab does.
+    pub skip_indentation: bool,
+    /// Grapheme index of the caret in the paragraph.
+    pub position: usize,
+    pub char_count: u64,
+    pub word_count: u64,
+    pub correct_count: u64,
+    pub incorrect_count: u64,
+    pub extra_count: u64,
+    pub missed_count: u64,
+    pub keystrokes: Vec<Keystroke>,
+    pub samples: Vec<Sample>,
+    /// When every typed word was last finished by a separator, in milliseconds.
+    pub word_ends: Vec<i64>,
+}
+
+impl Default for TypingSession {
+    fn default() -> Self {
+        TypingSession::new(String::new())
+    }
+}
+
+/// A paragraph word next to what was typed for it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordResult {
+    pub word: String,
+    pub typed: String,
+    pub is_correct: bool,
+    /// Milliseconds spent on the word, the first word starts at the first keystroke.
+    pub time: i64,
+}
+
+/// The results of a session over a duration in milliseconds.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Metrics {
+    pub wpm: i64,
+    pub raw_wpm: i64,
+    pub cpm: i64,
+    pub accuracy: f64,
+    pub consistency: f64,
+    pub burst_wpm: i64,
+    /// Milliseconds spent on the slowest word of `get_word_results`.
+    pub slowest_word_time: i64,
+}
+
+impl TypingSession {
+    pub fn new(paragraph: String) -> Self {
+        Self {
+            paragraph,
+            input: String::new(),
+            skip_indentation: true,
+            position: 0,
+            char_count: 0,
+            word_count: 0,
+            correct_count: 0,
+            incorrect_count: 0,
+            extra_count: 0,
+            missed_count: 0,
+            keystrokes: Vec::new(),
+            samples: Vec::new(),
+            word_ends: Vec::new(),
+        }
+    }
+
+    /// Types the keystrokes of a past run on the paragraph.
+    pub fn replay(paragraph: String, keystrokes: &[Keystroke]) -> Self {
+        let mut session = TypingSession::new(paragraph);
+        for keystroke in keystrokes {
+            session.type_char(keystroke.typed, keystroke.timestamp);
+        }
+        session
+    }
+
+    /// Clears the input and the results but keeps the paragraph.
+    pub fn reset(&mut self) {
+        let paragraph = std::mem::take(&mut self.paragraph);
+        *self = Self {
+            skip_indentation: self.skip_indentation,
+            ..TypingSession::new(paragraph)
+        };
+    }
+
+    /// Applies a key to the input and returns the keystroke it makes, `None` when it's
+    /// ignored. Deletions are passed as `BACKSPACE_CHAR` and `DELETE_WORD_CHAR`, tab fills
+    /// in indentation.
+    pub fn press(&mut self, typed: char, timestamp: i64) -> Option<Keystroke> {
+        let (expected, is_correct) = match typed {
+            BACKSPACE_CHAR => {
+                self.pop_input();
+                (None, false)
+            }
+            DELETE_WORD_CHAR => {
+                self.pop_input_word();
+                (None, false)
+            }
+            _ if is_separator(typed) || typed == '\t' => {
+                let separator = self.get_separator_input(typed)?;
+                let counted = self.count_separator_keystroke(&separator);
+                self.input.push_str(&separator);
+                counted
+            }
+            _ => {
+                self.input.push(typed);
+                self.count_keystroke()
+            }
+        };
+        self.update_progress(timestamp);
+        Some(Keystroke {
+            timestamp,
+            expected,
+            typed,
+            is_correct,
+            ..Keystroke::default()
+        })
+    }
+
+    /// Presses the key and keeps its keystroke, closing the samples of the seconds that
+    /// ended before it. Returns whether the key was taken.
+    pub fn type_char(&mut self, typed: char, timestamp: i64) -> bool {
+        self.sample(timestamp);
+        match self.press(typed, timestamp) {
+            Some(keystroke) => {
+                self.keystrokes.push(keystroke);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Closes a sample for every full second up to `elapsed_millis` that has none yet.
+    pub fn sample(&mut self, elapsed_millis: i64) {
+        while (self.samples.len() as i64) < elapsed_millis / 1000 {
+            let second = self.samples.len() as i64 + 1;
+            let wpm = calculate_word_speed(self.char_count, second * 1000);
+            let sample = get_sample(&self.keystrokes, second, wpm);
+            self.samples.push(sample);
+        }
+    }
+
+    pub fn pop_input(&mut self) -> Option<String> {
+        // a line break goes together with the indentation after it
+        let separator_start = self.input.trim_end_matches(is_separator).len();
+        if self.input[separator_start..].contains('\n') {
+            return Some(self.input.split_off(separator_start));
+        }
+        let (start, grapheme) = self.input.grapheme_indices(true).next_back()?;
+        let grapheme = grapheme.to_string();
+        self.input.truncate(start);
+        Some(grapheme)
+    }
+
+    pub fn pop_input_word(&mut self) {
+        // drop the separator first so the previous word is removed as a whole
+        let trimmed_length = self.input.trim_end_matches(is_separator).len();
+        let word_start = self.input[..trimmed_length]
+            .rfind(is_separator)
+            .map_or(0, |index| index + 1);
+        self.input.truncate(word_start);
+    }
+
+    /// Every typed character, right or wrong, which is what raw wpm is based on.
+    pub fn get_typed_count(&self) -> u64 {
+        self.correct_count + self.incorrect_count + self.extra_count
+    }
+
+    pub fn get_accuracy(&self) -> f64 {
+        calculate_accuracy(
+            self.correct_count,
+            self.get_typed_count() + self.missed_count,
+        )
+    }
+
+    pub fn is_finished(&self) -> bool {
+        is_paragraph_finished(&self.paragraph, &self.input)
+    }
+
+    /// Returns the finished words, and the last one once the paragraph is finished.
+    pub fn get_word_results(&self) -> Vec<WordResult> {
+        let typed_words = split_words(&self.input);
+        let finished_count = match self.is_finished() {
+            true => typed_words.len(),
+            false => typed_words.len() - 1,
+        };
+        let mut word_start = self
+            .keystrokes
+            .first()
+            .map_or(0, |keystroke| keystroke.timestamp);
+        let last_timestamp = self
+            .keystrokes
+            .last()
+            .map_or(0, |keystroke| keystroke.timestamp);
+
+        split_words(&self.paragraph)
+            .into_iter()
+            .zip(typed_words)
+            .take(finished_count)
+            .enumerate()
+            .map(|(index, (word, typed))| {
+                let word_end = self.word_ends.get(index).copied().unwrap_or(last_timestamp);
+                let time = word_end - word_start;
+                word_start = word_end;
+                WordResult {
+                    word: word.to_string(),
+                    typed: typed.to_string(),
+                    is_correct: word == typed,
+                    time,
+                }
+            })
+            .collect()
+    }
+
+    pub fn get_metrics(&self, duration: i64) -> Metrics {
+        Metrics {
+            wpm: calculate_word_speed(self.char_count, duration),
+            raw_wpm: calculate_word_speed(self.get_typed_count(), duration),
+            cpm: calculate_char_speed(self.char_count, duration),
+            accuracy: self.get_accuracy(),
+            consistency: calculate_consistency(&self.samples),
+            burst_wpm: calculate_burst_speed(&self.samples),
+            slowest_word_time: self
+                .get_word_results()
+                .iter()
+                .map(|word_result| word_result.time)
+                .max()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Returns what a space, line break or tab adds to the input, `None` when it's
+    /// ignored. A separator finishes a started word and never runs past the last one,
+    /// after that only the line breaks and indentation the paragraph expects are taken.
+    /// Tab fills in the rest of the indentation.
+    fn get_separator_input(&self, typed: char) -> Option<String> {
+        if self.input.is_empty() {
+            return None;
+        }
+        let word_index = split_words(&self.input).len() - 1;
+        let separators = split_separators(&self.paragraph);
+        let typed_separator = &self.input[self.input.trim_end_matches(is_separator).len()..];
+
+        if typed_separator.is_empty() {
+            let expected = separators.get(word_index)?;
+            if typed == '\t' {
+                return None;
+            }
+            if typed == '\n' && expected.starts_with('\n') && self.skip_indentation {
+                return Some(expected.to_string());
+            }
+            return Some(typed.to_string());
+        }
+
+        let rest = separators
+            .get(word_index - 1)?
+            .strip_prefix(typed_separator)?;
+        match typed {
+            '\t' if rest.starts_with(' ') => Some(rest.to_string()),
+            _ if rest.starts_with(typed) => Some(typed.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Updates the keystroke counters for a separator before it's added to the input and
+    /// returns what the paragraph expected together with whether the keystroke matched.
+    fn count_separator_keystroke(&mut self, separator: &str) -> (Option<String>, bool) {
+        // line breaks and indentation the paragraph expects before the next word
+        if self.input.ends_with(is_separator) {
+            self.correct_count += 1;
+            return (separator.get(..1).map(String::from), true);
+        }
+
+        let (word, typed_word) = get_current_word(&self.paragraph, &self.input);
+        let expected = split_separators(&self.paragraph)
+            .get(split_words(&self.input).len() - 1)
+            .and_then(|expected| expected.get(..1))
+            .map(String::from);
+        let missing_length = word
+            .graphemes(true)
+            .count()
+            .saturating_sub(typed_word.graphemes(true).count());
+        // an early separator skips the rest of the word
+        if missing_length > 0 {
+            self.missed_count += missing_length as u64;
+            return (expected, false);
+        }
+        if expected.as_deref() == separator.get(..1) {
+            self.correct_count += 1;
+            return (expected, true);
+        }
+        self.incorrect_count += 1;
+        (expected, false)
+    }
+
+    /// Updates the keystroke counters for the character that was just typed and returns
+    /// the grapheme the paragraph expected together with whether the keystroke matched it.
+    /// Graphemes that take several keystrokes count as correct while they are typed.
+    fn count_keystroke(&mut self) -> (Option<String>, bool) {
+        let (word, typed_word) = get_current_word(&self.paragraph, &self.input);
+        let typed_graphemes = typed_word.graphemes(true).collect::<Vec<&str>>();
+        let typed_grapheme = typed_graphemes.last().copied().unwrap_or_default();
+        let expected = word
+            .graphemes(true)
+            .nth(typed_graphemes.len().saturating_sub(1))
+            .map(String::from);
+
+        match expected {
+            Some(expected) if expected.starts_with(typed_grapheme) => {
+                self.correct_count += 1;
+                (Some(expected), true)
+            }
+            Some(expected) => {
+                self.incorrect_count += 1;
+                (Some(expected), false)
+            }
+            None => {
+                self.extra_count += 1;
+                (None, false)
+            }
+        }
+    }
+
+    fn update_progress(&mut self, timestamp: i64) {
+        self.position = get_caret_position(&self.paragraph, &self.input);
+        self.char_count = count_correct_chars(&self.paragraph, &self.input);
+        self.word_count = split_words(&self.input).len() as u64 - 1;
+
+        // a word typed again after a deletion ends when it's finished again
+        let word_count = self.word_count as usize;
+        if word_count > self.word_ends.len() {
+            self.word_ends.push(timestamp);
+        }
+        self.word_ends.truncate(word_count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_text(session: &mut TypingSession, text: &str, start: i64, step: i64) {
+        for (index, character) in text.chars().enumerate() {
+            session.type_char(character, start + index as i64 * step);
+        }
+    }
+
+    #[test]
+    fn test_press() {
+        let mut session = TypingSession::new("some words".to_string());
+        // separators are ignored before the first word
+        assert_eq!(session.press(' ', 0), None);
+
+        let keystroke = session.press('s', 100).unwrap();
+        assert_eq!(keystroke.expected.as_deref(), Some("s"));
+        assert!(keystroke.is_correct);
+        let keystroke = session.press('x', 200).unwrap();
+        assert_eq!(keystroke.expected.as_deref(), Some("o"));
+        assert!(!keystroke.is_correct);
+        let keystroke = session.press(BACKSPACE_CHAR, 300).unwrap();
+        assert_eq!(keystroke.typed, BACKSPACE_CHAR);
+        assert_eq!(session.input, "s");
+        assert_eq!(session.position, 1);
+        // keystrokes are only kept by type_char
+        assert!(session.keystrokes.is_empty());
+
+        // an early space skips the rest of the word
+        session.press(' ', 400);
+        assert_eq!(session.input, "s ");
+        assert_eq!(session.position, 5);
+        assert_eq!(session.word_count, 1);
+        assert_eq!(session.missed_count, 3);
+        assert_eq!(session.correct_count, 1);
+        assert_eq!(session.incorrect_count, 1);
+        assert_eq!(session.char_count, 2);
+
+        session.press(DELETE_WORD_CHAR, 500);
+        assert_eq!(session.input, "");
+        assert_eq!(session.position, 0);
+    }
+
+    #[test]
+    fn test_press_code() {
+        let mut session = TypingSession::new("{\n    x\n}".to_string());
+        session.press('{', 0);
+        session.press('\n', 100);
+        assert_eq!(session.input, "{\n    ");
+
+        session.reset();
+        session.skip_indentation = false;
+        session.press('{', 0);
+        session.press('\n', 100);
+        assert_eq!(session.input, "{\n");
+        session.press('\t', 200);
+        assert_eq!(session.input, "{\n    ");
+        assert_eq!(session.press('\t', 300), None);
+        assert_eq!(session.correct_count, 3);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut session = TypingSession::new("some words".to_string());
+        session.skip_indentation = false;
+        type_text(&mut session, "some", 0, 100);
+        session.sample(2000);
+        session.reset();
+        assert_eq!(session.paragraph, "some words");
+        assert!(!session.skip_indentation);
+        assert_eq!(session.input, "");
+        assert_eq!(session.correct_count, 0);
+        assert!(session.keystrokes.is_empty());
+        assert!(session.samples.is_empty());
+    }
+
+    #[test]
+    fn test_replay() {
+        let mut session = TypingSession::new("so we".to_string());
+        type_text(&mut session, "sx", 0, 200);
+        session.type_char(BACKSPACE_CHAR, 400);
+        type_text(&mut session, "o we", 600, 200);
+        assert!(session.is_finished());
+
+        let replay = TypingSession::replay("so we".to_string(), &session.keystrokes);
+        assert_eq!(replay, session);
+    }
+
+    #[test]
+    fn test_type_char_samples() {
+        let mut session = TypingSession::new("some words".to_string());
+        type_text(&mut session, "some ", 0, 200);
+        assert_eq!(session.samples.len(), 0);
+        // the first second closes before a keystroke past it is counted
+        type_text(&mut session, "wo", 1500, 300);
+        assert_eq!(session.samples.len(), 1);
+        assert_eq!(session.samples[0].wpm, 60);
+        assert_eq!(session.samples[0].raw_wpm, 60);
+    }
+
+    #[test]
+    fn test_word_results() {
+        let mut session = TypingSession::new("ab cd ef".to_string());
+        type_text(&mut session, "ab ", 1000, 100);
+        type_text(&mut session, "cx ", 1300, 200);
+        assert_eq!(
+            session.get_word_results(),
+            vec![
+                WordResult {
+                    word: "ab".to_string(),
+                    typed: "ab".to_string(),
+                    is_correct: true,
+                    time: 200,
+                },
+                WordResult {
+                    word: "cd".to_string(),
+                    typed: "cx".to_string(),
+                    is_correct: false,
+                    time: 500,
+                },
+            ]
+        );
+
+        // a deleted word is timed again when it's finished again
+        session.type_char(DELETE_WORD_CHAR, 2000);
+        type_text(&mut session, "cd ef", 2200, 100);
+        let word_results = session.get_word_results();
+        assert_eq!(word_results.len(), 3);
+        assert!(word_results[1].is_correct);
+        assert_eq!(word_results[1].time, 2400 - 1200);
+        // the last word ends with its last keystroke
+        assert_eq!(word_results[2].time, 200);
+    }
+
+    #[test]
+    fn test_metrics() {
+        let mut session = TypingSession::new("ab cd".to_string());
+        type_text(&mut session, "ab cx", 0, 250);
+        let metrics = session.get_metrics(6_000);
+        assert_eq!(metrics.wpm, 8);
+        assert_eq!(metrics.raw_wpm, 10);
+        assert_eq!(metrics.cpm, 40);
+        assert_eq!(metrics.accuracy, 80.0);
+        assert_eq!(metrics.slowest_word_time, 500);
+
+        session.sample(2_000);
+        let metrics = session.get_metrics(2_000);
+        assert_eq!(session.samples.len(), 2);
+        assert_eq!(metrics.burst_wpm, 30);
+        assert_eq!(metrics.consistency.round(), 40.0);
+
+        // the slowest finished word, the word being typed isn't timed yet
+        let mut session = TypingSession::new("ab cd ef".to_string());
+        type_text(&mut session, "ab ", 900, 200);
+        type_text(&mut session, "cd ", 1500, 500);
+        session.type_char('e', 2700);
+        assert_eq!(session.get_metrics(3_000).slowest_word_time, 1200);
+        assert_eq!(TypingSession::default().get_metrics(0).slowest_word_time, 0);
+    }
+}
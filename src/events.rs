@@ -0,0 +1,108 @@
+#[cfg(test)]
+use std::collections::VecDeque;
+use std::io::Error as StandardError;
+use std::time::Duration;
+
+use crossterm::event::{self, Event};
+#[cfg(test)]
+use crossterm::event::{KeyCode, KeyEvent};
+
+#[cfg(test)]
+use crate::clock::{Clock, ManualClock};
+
+/// Where the event loop reads terminal events from.
+pub trait EventSource {
+    /// Waits up to `timeout` for the next event, `None` when there was none.
+    fn next_event(&mut self, timeout: Duration) -> Result<Option<Event>, StandardError>;
+}
+
+/// Events of the terminal the app runs in.
+#[derive(Debug, Default)]
+pub struct CrosstermEvents;
+
+impl EventSource for CrosstermEvents {
+    fn next_event(&mut self, timeout: Duration) -> Result<Option<Event>, StandardError> {
+        if event::poll(timeout)? {
+            return event::read().map(Some);
+        }
+        Ok(None)
+    }
+}
+
+/// Replays a list of events on a manual clock. Every event waits its delay in milliseconds
+/// after the previous one, waiting moves the clock as if the time passed. Once the script
+/// runs out it keeps pressing esc so the app always exits. Only built for tests.
+#[cfg(test)]
+#[derive(Debug)]
+pub struct ScriptedEvents {
+    clock: ManualClock,
+    events: VecDeque<(i64, Event)>,
+    last_event_millis: i64,
+}
+
+#[cfg(test)]
+impl ScriptedEvents {
+    pub fn new(clock: ManualClock, events: Vec<(i64, Event)>) -> Self {
+        let last_event_millis = clock.now_millis();
+        Self {
+            clock,
+            events: events.into(),
+            last_event_millis,
+        }
+    }
+}
+
+#[cfg(test)]
+impl EventSource for ScriptedEvents {
+    fn next_event(&mut self, timeout: Duration) -> Result<Option<Event>, StandardError> {
+        let now = self.clock.now_millis();
+        let timeout = timeout.as_millis() as i64;
+        match self.events.pop_front() {
+            Some((delay, event)) if self.last_event_millis + delay <= now + timeout => {
+                let event_millis = (self.last_event_millis + delay).max(now);
+                self.clock.set(event_millis);
+                self.last_event_millis = event_millis;
+                Ok(Some(event))
+            }
+            Some(pending) => {
+                self.events.push_front(pending);
+                self.clock.advance(timeout);
+                Ok(None)
+            }
+            None => {
+                self.clock.advance(timeout);
+                Ok(Some(Event::Key(KeyEvent::from(KeyCode::Esc))))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(character: char) -> Event {
+        Event::Key(KeyEvent::from(KeyCode::Char(character)))
+    }
+
+    #[test]
+    fn test_scripted_events() {
+        let clock = ManualClock::new(1000);
+        let mut events = ScriptedEvents::new(clock.clone(), vec![(0, key('a')), (40, key('b'))]);
+        let timeout = Duration::from_millis(16);
+
+        assert_eq!(events.next_event(timeout).unwrap(), Some(key('a')));
+        assert_eq!(clock.now_millis(), 1000);
+        // nothing is due within the timeout, so the whole timeout passes
+        assert_eq!(events.next_event(timeout).unwrap(), None);
+        assert_eq!(events.next_event(timeout).unwrap(), None);
+        assert_eq!(clock.now_millis(), 1032);
+        // the event arrives when it's due, not at the end of the timeout
+        assert_eq!(events.next_event(timeout).unwrap(), Some(key('b')));
+        assert_eq!(clock.now_millis(), 1040);
+
+        let esc = Event::Key(KeyEvent::from(KeyCode::Esc));
+        assert_eq!(events.next_event(timeout).unwrap(), Some(esc));
+        assert_eq!(clock.now_millis(), 1056);
+    }
+}
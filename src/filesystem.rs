@@ -1,12 +1,17 @@
+use std::collections::HashMap;
 use std::fs;
 use std::io::Error as StandardError;
 use std::path::{Path, PathBuf};
 
 use dirs;
-use rand::{seq::SliceRandom, thread_rng};
+use rand::seq::{IteratorRandom, SliceRandom};
+use serde::Deserialize;
 use thiserror::Error;
 
-use crate::constants::{APP_PATH, WORD_LIST};
+use crate::constants::{Language, QuoteLength, APP_PATH};
+use crate::quote::Quote;
+use crate::snippet::Snippet;
+use crate::util::get_rng;
 
 #[derive(Error, Debug)]
 pub enum FileSystemError {
@@ -14,6 +19,29 @@ pub enum FileSystemError {
     GetAppConfigPath(),
     #[error("Could not create config folder: {0}")]
     CreateConfigFolder(StandardError),
+    #[error("Could not read file {0}: {1}")]
+    ReadFile(String, StandardError),
+    #[error("Could not write file {0}: {1}")]
+    WriteFile(String, StandardError),
+    #[error("Could not read word lists folder: {0}")]
+    ReadWordListsFolder(StandardError),
+    #[error("Invalid word list {0}: {1}")]
+    InvalidWordList(String, String),
+    #[error("Could not parse quotes: {0}")]
+    ParseQuotes(toml::de::Error),
+    #[error("There are no {0} quotes")]
+    MissingQuote(QuoteLength),
+    #[error("Could not parse snippets: {0}")]
+    ParseSnippets(toml::de::Error),
+    #[error("There are no {0} snippets")]
+    MissingSnippet(Language),
+}
+
+/// A word list picked from the word lists folder or passed by path.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordList {
+    pub name: String,
+    pub words: String,
 }
 
 pub fn get_app_config_path() -> Result<PathBuf, FileSystemError> {
@@ -32,13 +60,132 @@ pub fn create_config_folder(app_config_path: &PathBuf) -> Result<(), FileSystemE
     }
 }
 
-pub fn get_words() -> Vec<&'static str> {
-    WORD_LIST
-        .lines()
-        .collect::<Vec<&str>>()
-        .choose_multiple(&mut thread_rng(), 100)
+/// Picks random words from the list, a seed makes the pick repeatable. Lists shorter than
+/// the paragraph are reshuffled and repeated until there are enough words. Phrases count
+/// one word per word in them, the last one is cut to fit.
+pub fn get_words(word_list: &str, word_count: usize, seed: Option<u64>) -> Vec<&str> {
+    let lines = word_list.lines().collect::<Vec<&str>>();
+    let mut rng = get_rng(seed);
+    let mut picked_words = Vec::with_capacity(word_count);
+    while picked_words.len() < word_count && !lines.is_empty() {
+        let missing_count = word_count - picked_words.len();
+        let picked_lines = lines.choose_multiple(&mut rng, missing_count);
+        picked_words.extend(picked_lines.flat_map(|line| line.split_whitespace()));
+    }
+    picked_words.truncate(word_count);
+    picked_words
+}
+
+pub fn read_file(path: &Path) -> Result<String, FileSystemError> {
+    fs::read_to_string(path).map_err(|e| FileSystemError::ReadFile(path.display().to_string(), e))
+}
+
+pub fn write_file(path: &Path, content: &str) -> Result<(), FileSystemError> {
+    fs::write(path, content).map_err(|e| FileSystemError::WriteFile(path.display().to_string(), e))
+}
+
+/// Returns the `.txt` files of the word lists folder sorted by name, a missing folder
+/// has no lists.
+pub fn get_word_list_paths(word_lists_path: &Path) -> Result<Vec<PathBuf>, FileSystemError> {
+    if !word_lists_path.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut paths = fs::read_dir(word_lists_path)
+        .map_err(FileSystemError::ReadWordListsFolder)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "txt"))
+        .collect::<Vec<PathBuf>>();
+    paths.sort();
+    Ok(paths)
+}
+
+/// Reads a word list and names it after its file.
+pub fn load_word_list(path: &Path) -> Result<WordList, FileSystemError> {
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let words = read_file(path)?;
+    validate_word_list(&name, &words)?;
+    Ok(WordList { name, words })
+}
+
+/// Checks that a word list has one printable, unique word or phrase per line.
+pub fn validate_word_list(name: &str, words: &str) -> Result<(), FileSystemError> {
+    let invalid = |reason: String| FileSystemError::InvalidWordList(name.to_string(), reason);
+    if words.trim().is_empty() {
+        return Err(invalid("the file has no words".to_string()));
+    }
+
+    // first line number of every line, repeats are found without rescanning the list
+    let mut seen_lines = HashMap::new();
+    for (index, line) in words.lines().enumerate() {
+        let line_number = index + 1;
+        if line.trim().is_empty() {
+            return Err(invalid(format!("line {line_number} is empty")));
+        }
+        if line.chars().any(char::is_control) {
+            return Err(invalid(format!(
+                "line {line_number} has non-printable characters"
+            )));
+        }
+        if let Some(first_line_number) = seen_lines.insert(line, line_number) {
+            return Err(invalid(format!(
+                "line {line_number} repeats line {first_line_number}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct QuoteCorpus {
+    quotes: Vec<Quote>,
+}
+
+pub fn get_quotes(quotes: &str) -> Result<Vec<Quote>, FileSystemError> {
+    toml::from_str::<QuoteCorpus>(quotes)
+        .map(|corpus| corpus.quotes)
+        .map_err(FileSystemError::ParseQuotes)
+}
+
+/// Picks a random quote of the length group, a seed makes the pick repeatable.
+pub fn get_quote(
+    quotes: &[Quote],
+    length: QuoteLength,
+    seed: Option<u64>,
+) -> Result<Quote, FileSystemError> {
+    quotes
+        .iter()
+        .filter(|quote| quote.get_length() == length)
+        .choose(&mut get_rng(seed))
+        .cloned()
+        .ok_or(FileSystemError::MissingQuote(length))
+}
+
+#[derive(Deserialize)]
+struct SnippetCorpus {
+    snippets: Vec<Snippet>,
+}
+
+pub fn get_snippets(snippets: &str) -> Result<Vec<Snippet>, FileSystemError> {
+    toml::from_str::<SnippetCorpus>(snippets)
+        .map(|corpus| corpus.snippets)
+        .map_err(FileSystemError::ParseSnippets)
+}
+
+/// Picks a random snippet of the language, a seed makes the pick repeatable.
+pub fn get_snippet(
+    snippets: &[Snippet],
+    language: Language,
+    seed: Option<u64>,
+) -> Result<Snippet, FileSystemError> {
+    snippets
+        .iter()
+        .filter(|snippet| snippet.language == language)
+        .choose(&mut get_rng(seed))
         .cloned()
-        .collect()
+        .ok_or(FileSystemError::MissingSnippet(language))
 }
 
 pub fn file_exists(app_config_path: &Path, file_name: &str) -> bool {
@@ -49,9 +196,12 @@ pub fn file_exists(app_config_path: &Path, file_name: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::constants::{TEST_APP_PATH, TEST_DB_NAME};
+    use crate::constants::{
+        CONFIG_NAME, QUOTES, SNIPPETS, TEST_APP_PATH, TEST_DB_NAME, WORD_LIST, WORD_LISTS_PATH,
+    };
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_file_exists() {
         let mut path = PathBuf::new();
         let db_name = format!("fs_{TEST_DB_NAME}");
@@ -79,9 +229,201 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_read_file() {
+        let path = Path::new(TEST_APP_PATH).join("config").join(CONFIG_NAME);
+        let result = read_file(&path);
+        assert!(result.is_ok());
+        assert!(result.unwrap().contains("words 25"));
+
+        let path = Path::new(TEST_APP_PATH).join("idk.toml");
+        let result = read_file(&path);
+        assert!(matches!(result, Err(FileSystemError::ReadFile(_, _))));
+    }
+
+    #[test]
+    fn test_write_file() {
+        let path = Path::new(TEST_APP_PATH).join("write_test.txt");
+        let result = write_file(&path, "some words");
+        assert!(result.is_ok());
+        assert_eq!(read_file(&path).unwrap(), "some words");
+        let result = fs::remove_file(&path);
+        assert!(result.is_ok());
+
+        let path = Path::new(TEST_APP_PATH).join("idk").join("write_test.txt");
+        let result = write_file(&path, "some words");
+        assert!(matches!(result, Err(FileSystemError::WriteFile(_, _))));
+    }
+
+    #[test]
+    fn test_get_word_list_paths() {
+        let path = Path::new(TEST_APP_PATH)
+            .join("config")
+            .join(WORD_LISTS_PATH);
+        let result = get_word_list_paths(&path);
+        assert!(result.is_ok());
+        let file_names = result
+            .unwrap()
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect::<Vec<String>>();
+        assert_eq!(file_names, vec!["broken.txt", "products.txt", "rust.txt"]);
+
+        let result = get_word_list_paths(&Path::new(TEST_APP_PATH).join(WORD_LISTS_PATH));
+        assert!(result.is_ok());
+        assert!(result.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_load_word_list() {
+        let path = Path::new(TEST_APP_PATH)
+            .join("config")
+            .join(WORD_LISTS_PATH);
+        let result = load_word_list(&path.join("rust.txt"));
+        assert!(result.is_ok());
+        let word_list = result.unwrap();
+        assert_eq!(word_list.name, "rust");
+        assert!(word_list.words.lines().any(|word| word == "impl"));
+
+        let result = load_word_list(&path.join("broken.txt"));
+        assert!(matches!(
+            result,
+            Err(FileSystemError::InvalidWordList(name, _)) if name == "broken"
+        ));
+    }
+
+    #[test]
+    fn test_validate_word_list() {
+        assert!(validate_word_list("words", "fn\nimpl\nSelf\n").is_ok());
+        assert!(validate_word_list("words", "pull request\nmerge queue").is_ok());
+        assert!(validate_word_list("words", "").is_err());
+        assert!(validate_word_list("words", " \n\n").is_err());
+        assert!(validate_word_list("words", "fn\n\nimpl").is_err());
+        assert!(validate_word_list("words", "fn\nimpl\nfn").is_err());
+        assert!(validate_word_list("words", "fn\nim\u{7}pl").is_err());
+        assert!(validate_word_list("words", "fn\r\nimpl\r\n").is_ok());
+
+        let result = validate_word_list("words", "fn\nimpl\nfn");
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Invalid word list words: line 3 repeats line 1"
+        );
+    }
+
+    #[test]
+    fn test_get_quotes() {
+        let result = get_quotes(QUOTES);
+        assert!(result.is_ok());
+        let quotes = result.unwrap();
+        for length in [QuoteLength::Short, QuoteLength::Medium, QuoteLength::Long] {
+            assert!(quotes.iter().any(|quote| quote.get_length() == length));
+        }
+        for (index, quote) in quotes.iter().enumerate() {
+            // ids are stored with records so they have to stay unique
+            assert!(quotes[..index].iter().all(|other| other.id != quote.id));
+            assert!(!quote.source.is_empty());
+            // words are aligned on single spaces
+            assert_eq!(quote.text.trim(), quote.text);
+            assert!(!quote.text.contains("  "));
+            assert!(quote.text.is_ascii());
+        }
+
+        let result = get_quotes("[[quotes]]\nid = 1\ntext = \"no source\"");
+        assert!(matches!(result, Err(FileSystemError::ParseQuotes(_))));
+    }
+
+    #[test]
+    fn test_get_quote() {
+        let quotes = get_quotes(QUOTES).unwrap();
+        let result = get_quote(&quotes, QuoteLength::Long, None);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().get_length(), QuoteLength::Long);
+
+        let quote = get_quote(&quotes, QuoteLength::Short, Some(42)).unwrap();
+        assert_eq!(
+            get_quote(&quotes, QuoteLength::Short, Some(42)).unwrap(),
+            quote
+        );
+
+        let quotes = vec![Quote {
+            id: 1,
+            text: "Call me Ishmael.".to_string(),
+            source: "Herman Melville, Moby-Dick".to_string(),
+        }];
+        let result = get_quote(&quotes, QuoteLength::Medium, None);
+        assert!(matches!(
+            result,
+            Err(FileSystemError::MissingQuote(QuoteLength::Medium))
+        ));
+    }
+
+    #[test]
+    fn test_get_snippets() {
+        let result = get_snippets(SNIPPETS);
+        assert!(result.is_ok());
+        let snippets = result.unwrap();
+        for language in [
+            Language::Rust,
+            Language::Python,
+            Language::JavaScript,
+            Language::Shell,
+        ] {
+            assert!(snippets.iter().any(|snippet| snippet.language == language));
+        }
+        for snippet in snippets {
+            // the typed input can't reproduce tabs or whitespace around the text
+            assert_eq!(snippet.code.trim(), snippet.code);
+            assert!(!snippet.code.contains('\t'));
+            assert!(snippet.code.lines().all(|line| line.trim_end() == line));
+        }
+
+        let result = get_snippets("[[snippets]]\nlanguage = \"cobol\"\ncode = \"x\"");
+        assert!(matches!(result, Err(FileSystemError::ParseSnippets(_))));
+    }
+
+    #[test]
+    fn test_get_snippet() {
+        let snippets = get_snippets(SNIPPETS).unwrap();
+        let snippet = get_snippet(&snippets, Language::Python, Some(3)).unwrap();
+        assert_eq!(snippet.language, Language::Python);
+        assert_eq!(
+            get_snippet(&snippets, Language::Python, Some(3)).unwrap(),
+            snippet
+        );
+
+        let result = get_snippet(&snippets[..0], Language::Rust, None);
+        assert!(matches!(
+            result,
+            Err(FileSystemError::MissingSnippet(Language::Rust))
+        ));
+    }
+
     #[test]
     fn test_get_words() {
-        let words = get_words();
+        let words = get_words(WORD_LIST, 100, None);
         assert_eq!(words.len(), 100);
+        let words = get_words(WORD_LIST, 10, None);
+        assert_eq!(words.len(), 10);
+
+        let words = get_words("çağ\nüber\nnaïve\n日本語\n👍🏽", 5, None);
+        assert_eq!(words.len(), 5);
+        assert!(words.contains(&"日本語"));
+
+        let words = get_words(WORD_LIST, 25, Some(42));
+        assert_eq!(words, get_words(WORD_LIST, 25, Some(42)));
+        assert_ne!(words, get_words(WORD_LIST, 25, Some(43)));
+        // short lists are repeated to fill the paragraph
+        let word_list = "fn\nimpl\nSelf";
+        let words = get_words(word_list, 10, Some(42));
+        assert_eq!(words.len(), 10);
+        for word in ["fn", "impl", "Self"] {
+            assert!(words.iter().filter(|picked| **picked == word).count() >= 3);
+        }
+        assert_eq!(words, get_words(word_list, 10, Some(42)));
+        assert!(get_words("", 10, None).is_empty());
+        // phrases count every word in them
+        let words = get_words("pull request\nmerge queue", 3, Some(42));
+        assert_eq!(words.len(), 3);
+        assert!(words.iter().all(|word| !word.contains(' ')));
     }
 }
@@ -0,0 +1,154 @@
+use std::{fmt, str::FromStr};
+
+use crate::engine::TypingSession;
+use crate::keystroke::Keystroke;
+use crate::record::Record;
+
+/// The past run to race, picked in the menu or with `--ghost`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GhostTarget {
+    /// The fastest run of the selected mode.
+    Best,
+    /// The run saved with this record id.
+    Record(i32),
+}
+
+impl fmt::Display for GhostTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GhostTarget::Best => write!(f, "best"),
+            GhostTarget::Record(id) => write!(f, "record {}", id),
+        }
+    }
+}
+
+impl FromStr for GhostTarget {
+    type Err = String;
+
+    /// Parses "best" or a record id.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "best" => Ok(GhostTarget::Best),
+            id => id.parse::<i32>().map(GhostTarget::Record).map_err(|_| {
+                format!("invalid ghost \"{value}\", expected \"best\" or a record id")
+            }),
+        }
+    }
+}
+
+/// A past run replayed on the same paragraph, moving at its recorded pace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ghost {
+    pub record: Record,
+    /// Milliseconds since the start paired with the caret position after every keystroke.
+    pub progress: Vec<(i64, usize)>,
+}
+
+impl Ghost {
+    /// Replays the keystrokes of the record on the paragraph.
+    pub fn new(record: Record, keystrokes: &[Keystroke], paragraph: &str) -> Self {
+        let mut session = TypingSession::new(paragraph.to_string());
+        let progress = keystrokes
+            .iter()
+            .map(|keystroke| {
+                session.press(keystroke.typed, keystroke.timestamp);
+                (keystroke.timestamp, session.position)
+            })
+            .collect();
+        Self { record, progress }
+    }
+
+    /// Returns where the ghost's caret was `elapsed_millis` into its run.
+    pub fn get_position(&self, elapsed_millis: i64) -> usize {
+        let index = self
+            .progress
+            .partition_point(|(timestamp, _)| *timestamp <= elapsed_millis);
+        match index {
+            0 => 0,
+            _ => self.progress[index - 1].1,
+        }
+    }
+
+    /// Returns when the ghost's caret first reached the position, `None` if it never did.
+    pub fn get_time_at(&self, position: usize) -> Option<i64> {
+        if position == 0 {
+            return Some(0);
+        }
+        self.progress
+            .iter()
+            .find(|(_, ghost_position)| *ghost_position >= position)
+            .map(|(timestamp, _)| *timestamp)
+    }
+
+    /// Formats how far the caret at `position` is ahead of the ghost, in characters and
+    /// in seconds the ghost needed or needs to get there.
+    pub fn get_gap(&self, position: usize, elapsed_millis: i64) -> String {
+        let char_gap = position as i64 - self.get_position(elapsed_millis) as i64;
+        match self.get_time_at(position) {
+            Some(timestamp) => format!(
+                "ghost: {:+} chars {:+.1}s",
+                char_gap,
+                (timestamp - elapsed_millis) as f64 / 1000.0
+            ),
+            None => format!("ghost: {:+} chars", char_gap),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::BACKSPACE_CHAR;
+
+    fn get_test_ghost() -> Ghost {
+        let keystroke = |timestamp: i64, typed: char| Keystroke {
+            timestamp,
+            typed,
+            ..Keystroke::default()
+        };
+        let keystrokes = vec![
+            keystroke(200, 's'),
+            keystroke(400, 'x'),
+            keystroke(600, BACKSPACE_CHAR),
+            keystroke(800, 'o'),
+            keystroke(1000, ' '),
+            keystroke(1500, 'w'),
+        ];
+        Ghost::new(Record::default(), &keystrokes, "so we")
+    }
+
+    #[test]
+    fn test_ghost_target_from_str() {
+        assert_eq!("best".parse::<GhostTarget>(), Ok(GhostTarget::Best));
+        assert_eq!("12".parse::<GhostTarget>(), Ok(GhostTarget::Record(12)));
+        assert!("worst".parse::<GhostTarget>().is_err());
+        assert_eq!(GhostTarget::Record(3).to_string(), "record 3");
+    }
+
+    #[test]
+    fn test_ghost_progress() {
+        let ghost = get_test_ghost();
+        assert_eq!(
+            ghost.progress,
+            vec![(200, 1), (400, 2), (600, 1), (800, 2), (1000, 3), (1500, 4)]
+        );
+        assert_eq!(ghost.get_position(0), 0);
+        assert_eq!(ghost.get_position(500), 2);
+        assert_eq!(ghost.get_position(600), 1);
+        assert_eq!(ghost.get_position(5000), 4);
+
+        assert_eq!(ghost.get_time_at(0), Some(0));
+        assert_eq!(ghost.get_time_at(2), Some(400));
+        assert_eq!(ghost.get_time_at(3), Some(1000));
+        assert_eq!(ghost.get_time_at(5), None);
+    }
+
+    #[test]
+    fn test_ghost_gap() {
+        let ghost = get_test_ghost();
+        // the ghost is at 2 and reached 3 at one second
+        assert_eq!(ghost.get_gap(3, 900), "ghost: +1 chars +0.1s");
+        assert_eq!(ghost.get_gap(1, 1200), "ghost: -2 chars -1.0s");
+        assert_eq!(ghost.get_gap(5, 2000), "ghost: +1 chars");
+    }
+}
@@ -0,0 +1,84 @@
+use std::cmp::Ordering;
+
+use crate::constants::Mode;
+
+/// A tournament where players take turns typing the same text on one machine.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HotSeat {
+    pub player_count: usize,
+    /// Seed of the text every player types.
+    pub seed: u64,
+    pub mode: Mode,
+    /// Results of the finished turns in the order they were played.
+    pub results: Vec<HotSeatResult>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct HotSeatResult {
+    pub name: String,
+    pub wpm: i64,
+    pub accuracy: f64,
+}
+
+impl HotSeat {
+    pub fn new(player_count: usize, seed: u64, mode: Mode) -> Self {
+        Self {
+            player_count,
+            seed,
+            mode,
+            results: Vec::new(),
+        }
+    }
+
+    /// Returns the turn about to be played, counting from 1.
+    pub fn get_turn(&self) -> usize {
+        self.results.len() + 1
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.results.len() >= self.player_count
+    }
+
+    /// Ranks the results by wpm, accuracy breaks ties and earlier turns win the rest.
+    pub fn get_leaderboard(&self) -> Vec<&HotSeatResult> {
+        let mut leaderboard = self.results.iter().collect::<Vec<&HotSeatResult>>();
+        leaderboard.sort_by(|a, b| {
+            b.wpm.cmp(&a.wpm).then(
+                b.accuracy
+                    .partial_cmp(&a.accuracy)
+                    .unwrap_or(Ordering::Equal),
+            )
+        });
+        leaderboard
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hot_seat() {
+        let mut hot_seat = HotSeat::new(3, 7, Mode::Words(10));
+        assert_eq!(hot_seat.get_turn(), 1);
+        assert!(!hot_seat.is_finished());
+
+        let result = |name: &str, wpm: i64, accuracy: f64| HotSeatResult {
+            name: name.to_string(),
+            wpm,
+            accuracy,
+        };
+        hot_seat.results.push(result("ana", 60, 95.0));
+        hot_seat.results.push(result("bo", 72, 90.0));
+        assert_eq!(hot_seat.get_turn(), 3);
+        hot_seat.results.push(result("cy", 60, 98.0));
+        assert!(hot_seat.is_finished());
+
+        let names = hot_seat
+            .get_leaderboard()
+            .iter()
+            .map(|result| result.name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(names, vec!["bo", "cy", "ana"]);
+    }
+}
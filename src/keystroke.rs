@@ -0,0 +1,11 @@
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Keystroke {
+    pub id: i32,
+    /// Milliseconds since the test started.
+    pub timestamp: i64,
+    /// The grapheme the paragraph expected, `None` for deletions and past the end of a word.
+    pub expected: Option<String>,
+    /// The typed character, deletions are stored as `BACKSPACE_CHAR` and `DELETE_WORD_CHAR`.
+    pub typed: char,
+    pub is_correct: bool,
+}
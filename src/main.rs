@@ -1,31 +1,55 @@
+pub mod cli;
 pub mod client;
+pub mod clock;
+pub mod config;
 pub mod constants;
 pub mod controller;
+pub mod engine;
+pub mod events;
 pub mod filesystem;
+pub mod ghost;
+pub mod hot_seat;
+pub mod keystroke;
+pub mod profile;
+pub mod quote;
+pub mod race;
 pub mod record;
+pub mod sample;
+pub mod snippet;
 pub mod state;
+pub mod store;
+pub mod terminal;
 pub mod util;
 pub mod view;
 
 use std::io::stdout;
 
-use crossterm::{
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-    ExecutableCommand,
-};
+use clap::Parser;
 use ratatui::prelude::{CrosstermBackend, Terminal};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
-    let mut controller = controller::Controller::new();
-
-    stdout().execute(EnterAlternateScreen)?;
-    enable_raw_mode()?;
-    terminal.clear()?;
+    let cli = cli::Cli::parse();
+    let app_config_path = filesystem::get_app_config_path()?;
+    if let Some(command) = cli.command.as_ref().filter(|command| !command.is_race()) {
+        let (db_folder, db_name) = cli.get_db_location(app_config_path);
+        print!("{}", cli::run_command(command, db_folder, &db_name)?);
+        return Ok(());
+    }
 
-    controller.run(&mut terminal)?;
+    terminal::install_panic_hook(app_config_path);
+    let mut controller = controller::Controller::new();
+    let result = {
+        let _guard = terminal::TerminalGuard::enter()?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
+        terminal.clear()?;
+        controller.run(&mut terminal, &mut events::CrosstermEvents, &cli)
+    };
 
-    stdout().execute(LeaveAlternateScreen)?;
-    disable_raw_mode()?;
+    // the guard left the alternate screen, the error stays on the shell, only panics
+    // are crashes worth a report
+    if let Err(e) = result {
+        eprintln!("typefast: {e}");
+        std::process::exit(1);
+    }
     Ok(())
 }
@@ -0,0 +1,6 @@
+/// A person sharing the install, records are saved under the picked profile.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Profile {
+    pub id: i32,
+    pub name: String,
+}
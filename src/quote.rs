@@ -0,0 +1,19 @@
+use serde::Deserialize;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::constants::QuoteLength;
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+pub struct Quote {
+    /// Stable id of the quote in the corpus, records point to it.
+    pub id: i32,
+    pub text: String,
+    /// Who said or wrote the quote and where.
+    pub source: String,
+}
+
+impl Quote {
+    pub fn get_length(&self) -> QuoteLength {
+        QuoteLength::from_text_length(self.text.graphemes(true).count())
+    }
+}
@@ -0,0 +1,628 @@
+//! Local multiplayer races over TCP.
+//!
+//! Every message is one JSON object on its own line, tagged with a `type` field. The host
+//! runs the server and joins it like every other player, passing the token the server was
+//! created with. The player with the token is the host and the only one allowed to start
+//! a race.
+//!
+//! Players send:
+//! - `{"type":"join","name":"ana"}` once after connecting, the host adds `"host_token":7`
+//! - `{"type":"start","mode":"words 25","countdown":3}` to start a race, host only
+//! - `{"type":"progress","position":12,"wpm":48,"finished":false}` while typing
+//!
+//! The server sends:
+//! - `{"type":"welcome","player_id":1,"is_host":false}` as the answer to `join`
+//! - `{"type":"start","seed":42,"mode":"words 25","countdown":3}` to every player at once
+//! - `{"type":"players","players":[{"id":0,"name":"ana","position":12,"wpm":48,"finished":false}]}`
+//!   whenever a player joins, leaves or makes progress
+//! - `{"type":"closed"}` when the host leaves, the server stops right after
+//!
+//! Lines that don't parse are ignored so newer players can talk to older servers.
+
+use std::io::{BufRead, BufReader, Error as StandardError, Write};
+use std::net::{
+    IpAddr, Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::constants::RACE_WRITE_TIMEOUT_MILLIS;
+
+#[derive(Error, Debug)]
+pub enum RaceError {
+    #[error("Could not start the race server: {0}")]
+    BindError(StandardError),
+    #[error("Could not join the race: {0}")]
+    ConnectError(StandardError),
+    #[error("Lost the connection to the race: {0}")]
+    SendError(StandardError),
+    #[error("Could not encode race message: {0}")]
+    EncodeError(serde_json::Error),
+}
+
+/// Messages players send to the server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Join {
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        host_token: Option<u64>,
+    },
+    Start {
+        mode: String,
+        countdown: i64,
+    },
+    Progress(Progress),
+}
+
+/// Messages the server sends to players.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Welcome {
+        player_id: usize,
+        is_host: bool,
+    },
+    Start {
+        seed: u64,
+        mode: String,
+        countdown: i64,
+    },
+    Players {
+        players: Vec<Player>,
+    },
+    Closed,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Progress {
+    /// Graphemes of the paragraph behind the caret.
+    pub position: usize,
+    pub wpm: i64,
+    pub finished: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Player {
+    pub id: usize,
+    pub name: String,
+    #[serde(flatten)]
+    pub progress: Progress,
+}
+
+enum ServerEvent {
+    Connected(usize, TcpStream),
+    Message(usize, ClientMessage),
+    Disconnected(usize),
+}
+
+pub struct RaceServer {
+    listener: TcpListener,
+    /// Secret the hosting process joins with, so nobody else on the network can take host.
+    host_token: u64,
+}
+
+impl RaceServer {
+    pub fn bind(address: impl ToSocketAddrs) -> Result<Self, RaceError> {
+        let listener = TcpListener::bind(address).map_err(RaceError::BindError)?;
+        Ok(Self {
+            listener,
+            host_token: rand::random(),
+        })
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr, RaceError> {
+        self.listener.local_addr().map_err(RaceError::BindError)
+    }
+
+    pub fn host_token(&self) -> u64 {
+        self.host_token
+    }
+
+    /// Runs the server on its own thread until the host leaves, then stops listening.
+    pub fn spawn(self) -> JoinHandle<()> {
+        let (sender, receiver) = channel();
+        let is_closed = Arc::new(AtomicBool::new(false));
+        let wake_address = self.listener.local_addr().ok().map(|mut address| {
+            // a listener on every interface is reached through loopback
+            match address.ip() {
+                IpAddr::V4(ip) if ip.is_unspecified() => address.set_ip(Ipv4Addr::LOCALHOST.into()),
+                IpAddr::V6(ip) if ip.is_unspecified() => address.set_ip(Ipv6Addr::LOCALHOST.into()),
+                _ => {}
+            }
+            address
+        });
+        let listener = self.listener;
+        let host_token = self.host_token;
+        let is_accept_closed = Arc::clone(&is_closed);
+        thread::spawn(move || accept_players(listener, sender, is_accept_closed));
+        thread::spawn(move || {
+            serve(receiver, host_token);
+            // accepting blocks until somebody connects, so connect once to let it see the flag
+            is_closed.store(true, Ordering::SeqCst);
+            if let Some(address) = wake_address {
+                let _ = TcpStream::connect(address);
+            }
+        })
+    }
+}
+
+fn accept_players(listener: TcpListener, sender: Sender<ServerEvent>, is_closed: Arc<AtomicBool>) {
+    for (player_id, stream) in listener.incoming().flatten().enumerate() {
+        if is_closed.load(Ordering::SeqCst) {
+            return;
+        }
+        let Ok(writer) = stream.try_clone() else {
+            continue;
+        };
+        if sender
+            .send(ServerEvent::Connected(player_id, writer))
+            .is_err()
+        {
+            return;
+        }
+        let sender = sender.clone();
+        thread::spawn(move || {
+            for line in BufReader::new(stream).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if let Ok(message) = serde_json::from_str::<ClientMessage>(&line) {
+                    if sender
+                        .send(ServerEvent::Message(player_id, message))
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
+            }
+            let _ = sender.send(ServerEvent::Disconnected(player_id));
+        });
+    }
+}
+
+fn serve(receiver: Receiver<ServerEvent>, host_token: u64) {
+    let mut writers: Vec<(usize, TcpStream)> = Vec::new();
+    let mut players: Vec<Player> = Vec::new();
+    let mut host_id = None;
+
+    while let Ok(event) = receiver.recv() {
+        match event {
+            ServerEvent::Connected(player_id, writer) => {
+                // a player that stops reading is dropped instead of stalling everyone
+                let timeout = Duration::from_millis(RACE_WRITE_TIMEOUT_MILLIS);
+                if writer.set_write_timeout(Some(timeout)).is_ok() {
+                    writers.push((player_id, writer));
+                }
+            }
+            ServerEvent::Message(
+                player_id,
+                ClientMessage::Join {
+                    name,
+                    host_token: token,
+                },
+            ) => {
+                if host_id.is_none() && token == Some(host_token) {
+                    host_id = Some(player_id);
+                }
+                let is_host = host_id == Some(player_id);
+                players.retain(|player| player.id != player_id);
+                players.push(Player {
+                    id: player_id,
+                    name,
+                    ..Player::default()
+                });
+                send_to(
+                    &mut writers,
+                    player_id,
+                    &ServerMessage::Welcome { player_id, is_host },
+                );
+                broadcast(
+                    &mut writers,
+                    &ServerMessage::Players {
+                        players: players.clone(),
+                    },
+                );
+            }
+            ServerEvent::Message(player_id, ClientMessage::Start { mode, countdown }) => {
+                if host_id != Some(player_id) {
+                    continue;
+                }
+                players
+                    .iter_mut()
+                    .for_each(|player| player.progress = Progress::default());
+                let seed = rand::random();
+                broadcast(
+                    &mut writers,
+                    &ServerMessage::Start {
+                        seed,
+                        mode,
+                        countdown,
+                    },
+                );
+                broadcast(
+                    &mut writers,
+                    &ServerMessage::Players {
+                        players: players.clone(),
+                    },
+                );
+            }
+            ServerEvent::Message(player_id, ClientMessage::Progress(progress)) => {
+                if let Some(player) = players.iter_mut().find(|player| player.id == player_id) {
+                    player.progress = progress;
+                    broadcast(
+                        &mut writers,
+                        &ServerMessage::Players {
+                            players: players.clone(),
+                        },
+                    );
+                }
+            }
+            ServerEvent::Disconnected(player_id) => {
+                if host_id == Some(player_id) {
+                    broadcast(&mut writers, &ServerMessage::Closed);
+                    for (_, writer) in writers.iter() {
+                        let _ = writer.shutdown(Shutdown::Both);
+                    }
+                    return;
+                }
+                writers.retain(|(id, _)| *id != player_id);
+                players.retain(|player| player.id != player_id);
+                broadcast(
+                    &mut writers,
+                    &ServerMessage::Players {
+                        players: players.clone(),
+                    },
+                );
+            }
+        }
+    }
+}
+
+fn send_to(writers: &mut Vec<(usize, TcpStream)>, player_id: usize, message: &ServerMessage) {
+    writers.retain_mut(|(id, writer)| *id != player_id || write_or_drop(writer, message));
+}
+
+fn broadcast(writers: &mut Vec<(usize, TcpStream)>, message: &ServerMessage) {
+    writers.retain_mut(|(_, writer)| write_or_drop(writer, message));
+}
+
+/// Writes the message and returns whether the player is still connected. A write that
+/// fails or times out may have sent half a line, so the connection is shut down, the
+/// reading side then sees the player leave.
+fn write_or_drop(writer: &mut TcpStream, message: &ServerMessage) -> bool {
+    let is_sent = write_message(writer, message).is_ok();
+    if !is_sent {
+        let _ = writer.shutdown(Shutdown::Both);
+    }
+    is_sent
+}
+
+fn write_message<T: Serialize>(writer: &mut TcpStream, message: &T) -> Result<(), RaceError> {
+    let line = serde_json::to_string(message).map_err(RaceError::EncodeError)?;
+    writeln!(writer, "{line}").map_err(RaceError::SendError)
+}
+
+/// A player's connection to the race server, messages arrive on a background thread.
+pub struct RaceClient {
+    writer: TcpStream,
+    receiver: Receiver<ServerMessage>,
+}
+
+impl RaceClient {
+    /// Connects to the server and joins the race under the name, the token of the server
+    /// makes the player the host.
+    pub fn connect(
+        address: impl ToSocketAddrs,
+        name: &str,
+        host_token: Option<u64>,
+    ) -> Result<Self, RaceError> {
+        let stream = TcpStream::connect(address).map_err(RaceError::ConnectError)?;
+        let reader = stream.try_clone().map_err(RaceError::ConnectError)?;
+        let (sender, receiver) = channel();
+        thread::spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if let Ok(message) = serde_json::from_str::<ServerMessage>(&line) {
+                    if sender.send(message).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        let mut client = Self {
+            writer: stream,
+            receiver,
+        };
+        client.send(&ClientMessage::Join {
+            name: name.to_string(),
+            host_token,
+        })?;
+        Ok(client)
+    }
+
+    pub fn send(&mut self, message: &ClientMessage) -> Result<(), RaceError> {
+        write_message(&mut self.writer, message)
+    }
+
+    /// Returns the next message from the server without waiting.
+    pub fn try_recv(&self) -> Option<ServerMessage> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Waits up to `timeout` for the next message from the server.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<ServerMessage> {
+        self.receiver.recv_timeout(timeout).ok()
+    }
+}
+
+impl Drop for RaceClient {
+    fn drop(&mut self) {
+        // the reading thread holds a clone of the stream, shutting it down is what closes
+        // the connection and lets the server see the player leave
+        let _ = self.writer.shutdown(Shutdown::Both);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn spawn_server() -> (SocketAddr, u64) {
+        let server = RaceServer::bind("127.0.0.1:0").unwrap();
+        let address = server.local_addr().unwrap();
+        let host_token = server.host_token();
+        server.spawn();
+        (address, host_token)
+    }
+
+    /// Skips messages until one matches, the server sends player lists all the time.
+    fn wait_for<F>(client: &RaceClient, is_match: F) -> ServerMessage
+    where
+        F: Fn(&ServerMessage) -> bool,
+    {
+        loop {
+            let message = client
+                .recv_timeout(TIMEOUT)
+                .expect("no message from server");
+            if is_match(&message) {
+                return message;
+            }
+        }
+    }
+
+    #[test]
+    fn test_message_format() {
+        let message = ClientMessage::Progress(Progress {
+            position: 12,
+            wpm: 48,
+            finished: false,
+        });
+        assert_eq!(
+            serde_json::to_string(&message).unwrap(),
+            r#"{"type":"progress","position":12,"wpm":48,"finished":false}"#
+        );
+        let message = serde_json::from_str::<ServerMessage>(
+            r#"{"type":"players","players":[{"id":0,"name":"ana","position":3,"wpm":20,"finished":true}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            message,
+            ServerMessage::Players {
+                players: vec![Player {
+                    id: 0,
+                    name: "ana".to_string(),
+                    progress: Progress {
+                        position: 3,
+                        wpm: 20,
+                        finished: true
+                    }
+                }]
+            }
+        );
+        assert!(serde_json::from_str::<ClientMessage>(r#"{"type":"cheat"}"#).is_err());
+    }
+
+    #[test]
+    fn test_race() {
+        let (address, host_token) = spawn_server();
+        let mut host = RaceClient::connect(address, "ana", Some(host_token)).unwrap();
+        let message = wait_for(&host, |message| {
+            matches!(message, ServerMessage::Welcome { .. })
+        });
+        assert_eq!(
+            message,
+            ServerMessage::Welcome {
+                player_id: 0,
+                is_host: true
+            }
+        );
+
+        let mut guest = RaceClient::connect(address, "bo", None).unwrap();
+        let message = wait_for(&guest, |message| {
+            matches!(message, ServerMessage::Welcome { .. })
+        });
+        assert_eq!(
+            message,
+            ServerMessage::Welcome {
+                player_id: 1,
+                is_host: false
+            }
+        );
+        wait_for(
+            &host,
+            |message| matches!(message, ServerMessage::Players { players } if players.len() == 2),
+        );
+
+        // only the host starts races, everyone gets the same seed
+        let result = guest.send(&ClientMessage::Start {
+            mode: "time 15".to_string(),
+            countdown: 3,
+        });
+        assert!(result.is_ok());
+        let result = host.send(&ClientMessage::Start {
+            mode: "words 10".to_string(),
+            countdown: 2,
+        });
+        assert!(result.is_ok());
+        let is_start = |message: &ServerMessage| matches!(message, ServerMessage::Start { .. });
+        let host_start = wait_for(&host, is_start);
+        let guest_start = wait_for(&guest, is_start);
+        assert_eq!(host_start, guest_start);
+        assert!(matches!(
+            host_start,
+            ServerMessage::Start { mode, countdown: 2, .. } if mode == "words 10"
+        ));
+
+        let progress = Progress {
+            position: 7,
+            wpm: 55,
+            finished: false,
+        };
+        let result = guest.send(&ClientMessage::Progress(progress));
+        assert!(result.is_ok());
+        let message = wait_for(&host, |message| {
+            matches!(message, ServerMessage::Players { players }
+                if players.iter().any(|player| player.progress.position == 7))
+        });
+        let ServerMessage::Players { players } = message else {
+            unreachable!();
+        };
+        assert_eq!(players[1].name, "bo");
+        assert_eq!(players[1].progress, progress);
+
+        // players that leave drop out of the list
+        drop(guest);
+        wait_for(
+            &host,
+            |message| matches!(message, ServerMessage::Players { players } if players.len() == 1),
+        );
+    }
+
+    #[test]
+    fn test_stalled_player() {
+        let (address, host_token) = spawn_server();
+        let mut host = RaceClient::connect(address, "ana", Some(host_token)).unwrap();
+        wait_for(&host, |message| {
+            matches!(message, ServerMessage::Welcome { .. })
+        });
+
+        // a player that never reads, its long name fills the socket buffers quickly
+        let mut stalled = TcpStream::connect(address).unwrap();
+        let join = ClientMessage::Join {
+            name: "z".repeat(100_000),
+            host_token: None,
+        };
+        let result = write_message(&mut stalled, &join);
+        assert!(result.is_ok());
+        wait_for(
+            &host,
+            |message| matches!(message, ServerMessage::Players { players } if players.len() == 2),
+        );
+
+        // the race goes on without it
+        for position in 1..=300 {
+            let progress = Progress {
+                position,
+                ..Progress::default()
+            };
+            let result = host.send(&ClientMessage::Progress(progress));
+            assert!(result.is_ok());
+        }
+        wait_for(&host, |message| {
+            matches!(message, ServerMessage::Players { players }
+                if players.len() == 1 && players[0].progress.position == 300)
+        });
+    }
+
+    #[test]
+    fn test_host_token() {
+        let (address, host_token) = spawn_server();
+        let is_welcome = |message: &ServerMessage| matches!(message, ServerMessage::Welcome { .. });
+
+        // joining first or with a wrong token doesn't make a player host
+        let guest = RaceClient::connect(address, "bo", None).unwrap();
+        let message = wait_for(&guest, is_welcome);
+        assert!(matches!(
+            message,
+            ServerMessage::Welcome { is_host: false, .. }
+        ));
+        let guest = RaceClient::connect(address, "cy", Some(host_token.wrapping_add(1))).unwrap();
+        let message = wait_for(&guest, is_welcome);
+        assert!(matches!(
+            message,
+            ServerMessage::Welcome { is_host: false, .. }
+        ));
+
+        let host = RaceClient::connect(address, "ana", Some(host_token)).unwrap();
+        let message = wait_for(&host, is_welcome);
+        assert_eq!(
+            message,
+            ServerMessage::Welcome {
+                player_id: 2,
+                is_host: true
+            }
+        );
+
+        let message = ClientMessage::Join {
+            name: "ana".to_string(),
+            host_token: None,
+        };
+        assert_eq!(
+            serde_json::to_string(&message).unwrap(),
+            r#"{"type":"join","name":"ana"}"#
+        );
+    }
+
+    #[test]
+    fn test_host_leaves() {
+        let server = RaceServer::bind("127.0.0.1:0").unwrap();
+        let address = server.local_addr().unwrap();
+        let host_token = server.host_token();
+        let handle = server.spawn();
+        let host = RaceClient::connect(address, "ana", Some(host_token)).unwrap();
+        wait_for(&host, |message| {
+            matches!(message, ServerMessage::Welcome { .. })
+        });
+        let guest = RaceClient::connect(address, "bo", None).unwrap();
+        wait_for(
+            &guest,
+            |message| matches!(message, ServerMessage::Players { players } if players.len() == 2),
+        );
+
+        // the guest is told and the server stops listening
+        drop(host);
+        wait_for(&guest, |message| *message == ServerMessage::Closed);
+        assert!(handle.join().is_ok());
+        let is_refused = (0..50).any(|_| {
+            thread::sleep(Duration::from_millis(20));
+            RaceClient::connect(address, "cy", None).is_err()
+        });
+        assert!(is_refused);
+    }
+
+    #[test]
+    fn test_connect_error() {
+        let server = RaceServer::bind("127.0.0.1:0").unwrap();
+        let address = server.local_addr().unwrap();
+        drop(server);
+        let result = RaceClient::connect(address, "ana", None);
+        assert!(matches!(result, Err(RaceError::ConnectError(_))));
+        assert!(matches!(
+            RaceServer::bind("256.0.0.1:0"),
+            Err(RaceError::BindError(_))
+        ));
+    }
+}
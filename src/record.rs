@@ -4,4 +4,23 @@ pub struct Record {
     pub wpm: i64,
     pub cpm: i64,
     pub date: String,
+    pub raw_wpm: i64,
+    pub accuracy: f64,
+    pub mode: String,
+    pub consistency: f64,
+    pub burst_wpm: i64,
+    pub slowest_word_time: i64,
+    /// The quote typed in quote mode.
+    pub quote_id: Option<i32>,
+    /// Seed the paragraph was generated from, typing it again gives the same text.
+    pub seed: Option<u64>,
+    /// The player the record was saved under, `None` for guests.
+    pub player_id: Option<i32>,
+    /// The word list the paragraph was picked from, `None` when the text doesn't depend on
+    /// the settings of the player, like quotes, snippets, the daily challenge and races.
+    pub word_list: Option<String>,
+    /// Whether punctuation and capitals were mixed into the paragraph.
+    pub punctuation: bool,
+    /// Whether numbers were mixed into the paragraph.
+    pub numbers: bool,
 }
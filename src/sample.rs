@@ -0,0 +1,11 @@
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Sample {
+    /// The second of the test this sample closes.
+    pub second: i64,
+    /// Net wpm from the start of the test up to this second.
+    pub wpm: i64,
+    /// Raw wpm of the keystrokes typed within this second alone.
+    pub raw_wpm: i64,
+    /// Wrong keystrokes typed within this second.
+    pub errors: u64,
+}
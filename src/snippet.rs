@@ -0,0 +1,10 @@
+use serde::Deserialize;
+
+use crate::constants::Language;
+
+/// A source snippet of the bundled corpus, typed line by line in code mode.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Snippet {
+    pub language: Language,
+    pub code: String,
+}
@@ -1,21 +1,58 @@
-use chrono::Local;
-
-use crate::constants::Page;
+use crate::clock::SharedClock;
+use crate::config::Config;
+use crate::constants::{Mode, Page, RecordColumn, DEFAULT_WORD_LIST_NAME, WORD_LIST};
+use crate::engine::TypingSession;
+use crate::filesystem::WordList;
+use crate::ghost::{Ghost, GhostTarget};
+use crate::hot_seat::HotSeat;
+use crate::keystroke::Keystroke;
+use crate::profile::Profile;
+use crate::quote::Quote;
+use crate::race::Player;
 use crate::record::Record;
+use crate::sample::Sample;
 
 #[derive(Debug, Default)]
 pub struct State {
     pub is_running: bool,
     pub page: Page,
     pub error: String,
-    pub position: i32,
-    pub paragraph: String,
+    pub session: TypingSession,
     pub timer: i64,
-    pub char_count: u64,
-    pub word_count: u64,
     pub records: Vec<Record>,
+    /// Row of the records table under the cursor, counted over all pages.
+    pub record_index: i32,
+    pub record_count: usize,
+    pub record_column: RecordColumn,
+    pub record_ascending: bool,
+    /// Id of the fastest of the listed records.
+    pub record_best: Option<i32>,
     pub menu_index: i32,
+    pub mode: Mode,
+    pub duration: i64,
     pub reference_timestamp: i64,
+    pub config: Config,
+    pub word_list: Option<WordList>,
+    pub word_lists: Vec<WordList>,
+    pub seed: Option<u64>,
+    pub paragraph_seed: Option<u64>,
+    pub quote: Option<Quote>,
+    pub quote_best: Option<i64>,
+    pub punctuation: bool,
+    pub numbers: bool,
+    pub ghost_target: Option<GhostTarget>,
+    pub ghost: Option<Ghost>,
+    pub race_player_id: Option<usize>,
+    pub is_race_host: bool,
+    pub race_players: Vec<Player>,
+    pub profiles: Vec<Profile>,
+    pub profile: Option<Profile>,
+    pub profile_index: i32,
+    pub profile_name: String,
+    pub player_bests: Vec<(String, i64)>,
+    pub hot_seat_size: usize,
+    pub hot_seat: Option<HotSeat>,
+    pub clock: SharedClock,
 }
 
 impl State {
@@ -44,18 +81,33 @@ impl State {
         self.page = page;
     }
 
-    pub fn get_position(&self) -> i32 {
-        self.position
+    pub fn get_session(&self) -> &TypingSession {
+        &self.session
     }
-    pub fn set_position(&mut self, position: i32) {
-        self.position = position
+    pub fn get_session_mut(&mut self) -> &mut TypingSession {
+        &mut self.session
+    }
+
+    pub fn get_position(&self) -> i32 {
+        self.session.position as i32
     }
 
     pub fn get_paragraph(&self) -> &String {
-        &self.paragraph
+        &self.session.paragraph
     }
     pub fn set_paragraph(&mut self, new_paragraph: String) {
-        self.paragraph = new_paragraph;
+        self.session.paragraph = new_paragraph;
+    }
+
+    pub fn get_input(&self) -> &String {
+        &self.session.input
+    }
+
+    pub fn pop_input(&mut self) -> Option<String> {
+        self.session.pop_input()
+    }
+    pub fn pop_input_word(&mut self) {
+        self.session.pop_input_word();
     }
 
     pub fn get_timer(&self) -> i64 {
@@ -66,17 +118,44 @@ impl State {
     }
 
     pub fn get_word_count(&self) -> u64 {
-        self.word_count
-    }
-    pub fn set_word_count(&mut self, word_count: u64) {
-        self.word_count = word_count;
+        self.session.word_count
     }
 
     pub fn get_char_count(&self) -> u64 {
-        self.char_count
+        self.session.char_count
     }
-    pub fn set_char_count(&mut self, char_count: u64) {
-        self.char_count = char_count;
+
+    pub fn get_correct_count(&self) -> u64 {
+        self.session.correct_count
+    }
+
+    pub fn get_incorrect_count(&self) -> u64 {
+        self.session.incorrect_count
+    }
+
+    pub fn get_extra_count(&self) -> u64 {
+        self.session.extra_count
+    }
+
+    pub fn get_missed_count(&self) -> u64 {
+        self.session.missed_count
+    }
+
+    /// Every typed character, right or wrong, which is what raw wpm is based on.
+    pub fn get_typed_count(&self) -> u64 {
+        self.session.get_typed_count()
+    }
+
+    pub fn get_accuracy(&self) -> f64 {
+        self.session.get_accuracy()
+    }
+
+    pub fn get_keystrokes(&self) -> &Vec<Keystroke> {
+        &self.session.keystrokes
+    }
+
+    pub fn get_samples(&self) -> &Vec<Sample> {
+        &self.session.samples
     }
 
     pub fn get_records(&self) -> &Vec<Record> {
@@ -86,6 +165,41 @@ impl State {
         self.records = records;
     }
 
+    pub fn get_record_index(&self) -> i32 {
+        self.record_index
+    }
+    pub fn set_record_index(&mut self, record_index: i32) {
+        self.record_index = record_index;
+    }
+
+    pub fn get_record_count(&self) -> usize {
+        self.record_count
+    }
+    pub fn set_record_count(&mut self, record_count: usize) {
+        self.record_count = record_count;
+    }
+
+    pub fn get_record_column(&self) -> RecordColumn {
+        self.record_column
+    }
+    pub fn set_record_column(&mut self, record_column: RecordColumn) {
+        self.record_column = record_column;
+    }
+
+    pub fn get_record_ascending(&self) -> bool {
+        self.record_ascending
+    }
+    pub fn set_record_ascending(&mut self, record_ascending: bool) {
+        self.record_ascending = record_ascending;
+    }
+
+    pub fn get_record_best(&self) -> Option<i32> {
+        self.record_best
+    }
+    pub fn set_record_best(&mut self, record_best: Option<i32>) {
+        self.record_best = record_best;
+    }
+
     pub fn get_menu_index(&self) -> i32 {
         self.menu_index
     }
@@ -93,49 +207,252 @@ impl State {
         self.menu_index = menu_index;
     }
 
+    pub fn get_mode(&self) -> Mode {
+        self.mode
+    }
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
+    /// Returns how long the finished test took in milliseconds.
+    pub fn get_duration(&self) -> i64 {
+        self.duration
+    }
+    pub fn set_duration(&mut self, duration: i64) {
+        self.duration = duration;
+    }
+
     pub fn reset_stats(&mut self) {
-        self.set_char_count(0);
-        self.set_position(0);
-        self.set_word_count(0);
+        self.session.reset();
+        self.set_duration(0);
     }
 
     pub fn get_reference_timestamp(&self) -> i64 {
         self.reference_timestamp
     }
     pub fn set_reference_timestamp(&mut self, timestamp: Option<i64>) {
-        self.reference_timestamp = timestamp.unwrap_or(Local::now().timestamp_millis());
+        self.reference_timestamp = timestamp.unwrap_or(self.clock.now_millis());
     }
 
-    pub fn get_elapsed_time(&self) -> i64 {
+    pub fn get_elapsed_millis(&self) -> i64 {
         if self.reference_timestamp == 0 {
             0
         } else {
-            (Local::now().timestamp_millis() - self.get_reference_timestamp()) / 1000
+            self.clock.now_millis() - self.get_reference_timestamp()
         }
     }
+
+    /// Returns the elapsed whole seconds, for display.
+    pub fn get_elapsed_time(&self) -> i64 {
+        self.get_elapsed_millis() / 1000
+    }
+
+    pub fn get_clock(&self) -> &SharedClock {
+        &self.clock
+    }
+    pub fn set_clock(&mut self, clock: SharedClock) {
+        self.clock = clock;
+    }
+
+    pub fn get_config(&self) -> &Config {
+        &self.config
+    }
+    pub fn set_config(&mut self, config: Config) {
+        self.session.skip_indentation = config.skip_indentation;
+        self.config = config;
+    }
+
+    /// Returns the words paragraphs are built from, the bundled list unless one was picked.
+    pub fn get_word_list(&self) -> &str {
+        self.word_list
+            .as_ref()
+            .map_or(WORD_LIST, |word_list| word_list.words.as_str())
+    }
+    pub fn get_word_list_name(&self) -> &str {
+        self.word_list
+            .as_ref()
+            .map_or(DEFAULT_WORD_LIST_NAME, |word_list| word_list.name.as_str())
+    }
+    pub fn set_word_list(&mut self, word_list: Option<WordList>) {
+        self.word_list = word_list;
+    }
+
+    pub fn get_word_lists(&self) -> &Vec<WordList> {
+        &self.word_lists
+    }
+    pub fn set_word_lists(&mut self, word_lists: Vec<WordList>) {
+        self.word_lists = word_lists;
+    }
+
+    pub fn get_quote(&self) -> &Option<Quote> {
+        &self.quote
+    }
+    pub fn set_quote(&mut self, quote: Option<Quote>) {
+        self.quote = quote;
+    }
+
+    /// Returns the best wpm on the current quote before this test.
+    pub fn get_quote_best(&self) -> Option<i64> {
+        self.quote_best
+    }
+    pub fn set_quote_best(&mut self, quote_best: Option<i64>) {
+        self.quote_best = quote_best;
+    }
+
+    pub fn get_ghost_target(&self) -> Option<GhostTarget> {
+        self.ghost_target
+    }
+    pub fn set_ghost_target(&mut self, ghost_target: Option<GhostTarget>) {
+        self.ghost_target = ghost_target;
+    }
+
+    pub fn get_ghost(&self) -> &Option<Ghost> {
+        &self.ghost
+    }
+    pub fn set_ghost(&mut self, ghost: Option<Ghost>) {
+        self.ghost = ghost;
+    }
+
+    /// Returns the id the race server gave this player, `None` outside of races.
+    pub fn get_race_player_id(&self) -> Option<usize> {
+        self.race_player_id
+    }
+    pub fn set_race_player_id(&mut self, race_player_id: Option<usize>) {
+        self.race_player_id = race_player_id;
+    }
+
+    pub fn get_is_race_host(&self) -> bool {
+        self.is_race_host
+    }
+    pub fn set_is_race_host(&mut self, is_race_host: bool) {
+        self.is_race_host = is_race_host;
+    }
+
+    pub fn get_race_players(&self) -> &Vec<Player> {
+        &self.race_players
+    }
+    pub fn set_race_players(&mut self, race_players: Vec<Player>) {
+        self.race_players = race_players;
+    }
+
+    pub fn get_profiles(&self) -> &Vec<Profile> {
+        &self.profiles
+    }
+    pub fn set_profiles(&mut self, profiles: Vec<Profile>) {
+        self.profiles = profiles;
+    }
+
+    /// Returns the profile records are saved under, `None` for guests.
+    pub fn get_profile(&self) -> &Option<Profile> {
+        &self.profile
+    }
+    pub fn set_profile(&mut self, profile: Option<Profile>) {
+        self.profile = profile;
+    }
+
+    /// Returns the row under the cursor of the profile picker, guests come first.
+    pub fn get_profile_index(&self) -> i32 {
+        self.profile_index
+    }
+    pub fn set_profile_index(&mut self, profile_index: i32) {
+        self.profile_index = profile_index;
+    }
+
+    /// Returns the name typed into the profile picker for a new profile.
+    pub fn get_profile_name(&self) -> &String {
+        &self.profile_name
+    }
+    pub fn set_profile_name(&mut self, profile_name: String) {
+        self.profile_name = profile_name;
+    }
+
+    pub fn get_player_bests(&self) -> &Vec<(String, i64)> {
+        &self.player_bests
+    }
+    pub fn set_player_bests(&mut self, player_bests: Vec<(String, i64)>) {
+        self.player_bests = player_bests;
+    }
+
+    /// Returns the players of the next hot seat tournament, 0 when it's off.
+    pub fn get_hot_seat_size(&self) -> usize {
+        self.hot_seat_size
+    }
+    pub fn set_hot_seat_size(&mut self, hot_seat_size: usize) {
+        self.hot_seat_size = hot_seat_size;
+    }
+
+    pub fn get_hot_seat(&self) -> &Option<HotSeat> {
+        &self.hot_seat
+    }
+    pub fn get_hot_seat_mut(&mut self) -> Option<&mut HotSeat> {
+        self.hot_seat.as_mut()
+    }
+    pub fn set_hot_seat(&mut self, hot_seat: Option<HotSeat>) {
+        self.hot_seat = hot_seat;
+    }
+
+    pub fn get_punctuation(&self) -> bool {
+        self.punctuation
+    }
+    pub fn set_punctuation(&mut self, punctuation: bool) {
+        self.punctuation = punctuation;
+    }
+
+    pub fn get_numbers(&self) -> bool {
+        self.numbers
+    }
+    pub fn set_numbers(&mut self, numbers: bool) {
+        self.numbers = numbers;
+    }
+
+    pub fn get_seed(&self) -> Option<u64> {
+        self.seed
+    }
+    pub fn set_seed(&mut self, seed: Option<u64>) {
+        self.seed = seed;
+    }
+
+    pub fn get_paragraph_seed(&self) -> Option<u64> {
+        self.paragraph_seed
+    }
+    pub fn set_paragraph_seed(&mut self, paragraph_seed: Option<u64>) {
+        self.paragraph_seed = paragraph_seed;
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clock::ManualClock;
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_state_initialization() {
         let state = State::new();
         assert_eq!(state.is_running, false);
         assert_eq!(state.page, Page::default());
         assert_eq!(state.error, "");
-        assert_eq!(state.position, 0);
-        assert_eq!(state.paragraph, "");
+        assert_eq!(state.session.position, 0);
+        assert_eq!(state.session.paragraph, "");
+        assert_eq!(state.session.input, "");
         assert_eq!(state.timer, 0);
-        assert_eq!(state.char_count, 0);
-        assert_eq!(state.word_count, 0);
+        assert_eq!(state.session.char_count, 0);
+        assert_eq!(state.session.word_count, 0);
+        assert_eq!(state.session.correct_count, 0);
+        assert_eq!(state.session.incorrect_count, 0);
+        assert_eq!(state.session.extra_count, 0);
+        assert_eq!(state.session.missed_count, 0);
+        assert!(state.session.keystrokes.is_empty());
+        assert!(state.session.samples.is_empty());
         assert!(state.records.is_empty());
         assert_eq!(state.menu_index, 0);
+        assert_eq!(state.mode, Mode::Time(60));
+        assert_eq!(state.duration, 0);
         assert_eq!(state.reference_timestamp, 0);
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_set_get_is_running() {
         let mut state = State::new();
         state.set_is_running(true);
@@ -151,6 +468,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_set_get_page() {
         let mut state = State::new();
         let page = Page::default();
@@ -158,11 +476,21 @@ mod tests {
         assert_eq!(state.get_page(), &page);
     }
 
+    /// Types the text one key every 100 milliseconds, the way the controller does.
+    fn type_text(state: &mut State, text: &str) {
+        for (index, character) in text.chars().enumerate() {
+            state
+                .get_session_mut()
+                .type_char(character, index as i64 * 100);
+        }
+    }
+
     #[test]
-    fn test_set_get_position() {
+    fn test_get_position() {
         let mut state = State::new();
-        state.set_position(10);
-        assert_eq!(state.get_position(), 10);
+        state.set_paragraph("some words".to_string());
+        type_text(&mut state, "some w");
+        assert_eq!(state.get_position(), 6);
     }
 
     #[test]
@@ -173,6 +501,170 @@ mod tests {
         assert_eq!(state.get_paragraph(), &paragraph);
     }
 
+    #[test]
+    fn test_get_input() {
+        let mut state = State::new();
+        state.set_paragraph("some".to_string());
+        type_text(&mut state, "some");
+        assert_eq!(state.get_input(), "some");
+    }
+
+    #[test]
+    fn test_push_pop_input() {
+        let mut state = State::new();
+        state.get_session_mut().press('a', 0);
+        state.get_session_mut().press('b', 100);
+        assert_eq!(state.get_input(), "ab");
+        assert_eq!(state.pop_input(), Some("b".to_string()));
+        assert_eq!(state.get_input(), "a");
+        assert_eq!(state.pop_input(), Some("a".to_string()));
+        assert_eq!(state.pop_input(), None);
+
+        // whole grapheme clusters are removed at once
+        state.reset_stats();
+        state.set_paragraph("né👍🏽".to_string());
+        type_text(&mut state, "né👍🏽");
+        assert_eq!(state.pop_input(), Some("👍🏽".to_string()));
+        state.get_session_mut().press('e', 200);
+        state.get_session_mut().press('\u{301}', 300);
+        assert_eq!(state.pop_input(), Some("e\u{301}".to_string()));
+        assert_eq!(state.get_input(), "né");
+
+        // a line break is removed with its indentation
+        state.reset_stats();
+        state.set_paragraph("{\n    x\n    }".to_string());
+        type_text(&mut state, "{\nx\n");
+        assert_eq!(state.get_input(), "{\n    x\n    ");
+        assert_eq!(state.pop_input(), Some("\n    ".to_string()));
+        assert_eq!(state.get_input(), "{\n    x");
+        state.reset_stats();
+        state.set_paragraph("{  }".to_string());
+        type_text(&mut state, "{  ");
+        assert_eq!(state.pop_input(), Some(" ".to_string()));
+    }
+
+    #[test]
+    fn test_pop_input_word() {
+        let mut state = State::new();
+        state.set_paragraph("some words typed".to_string());
+        type_text(&mut state, "some words ty");
+        state.pop_input_word();
+        assert_eq!(state.get_input(), "some words ");
+        state.pop_input_word();
+        assert_eq!(state.get_input(), "some ");
+        state.pop_input_word();
+        assert_eq!(state.get_input(), "");
+        state.pop_input_word();
+        assert_eq!(state.get_input(), "");
+
+        state.reset_stats();
+        state.set_paragraph("fn main() {\n    let x = 1;\n}".to_string());
+        type_text(&mut state, "fn main() {\nlet");
+        assert_eq!(state.get_input(), "fn main() {\n    let");
+        state.pop_input_word();
+        assert_eq!(state.get_input(), "fn main() {\n    ");
+        state.pop_input_word();
+        assert_eq!(state.get_input(), "fn main() ");
+    }
+
+    #[test]
+    fn test_set_get_record_table() {
+        let mut state = State::new();
+        assert_eq!(state.get_record_column(), RecordColumn::Date);
+        assert!(!state.get_record_ascending());
+        state.set_record_index(12);
+        state.set_record_count(40);
+        state.set_record_column(RecordColumn::Wpm);
+        state.set_record_ascending(true);
+        state.set_record_best(Some(3));
+        assert_eq!(state.get_record_index(), 12);
+        assert_eq!(state.get_record_count(), 40);
+        assert_eq!(state.get_record_column(), RecordColumn::Wpm);
+        assert!(state.get_record_ascending());
+        assert_eq!(state.get_record_best(), Some(3));
+    }
+
+    #[test]
+    fn test_set_get_mode() {
+        let mut state = State::new();
+        state.set_mode(Mode::Words(25));
+        assert_eq!(state.get_mode(), Mode::Words(25));
+    }
+
+    #[test]
+    fn test_set_get_duration() {
+        let mut state = State::new();
+        state.set_duration(30);
+        assert_eq!(state.get_duration(), 30);
+    }
+
+    #[test]
+    fn test_reset_stats() {
+        let mut state = State::new();
+        state.set_paragraph("some words".to_string());
+        type_text(&mut state, "sxmee w");
+        state.get_session_mut().sample(1500);
+        state.set_duration(30);
+        assert_eq!(state.get_typed_count(), 7);
+        state.reset_stats();
+        assert_eq!(state.get_input(), "");
+        assert_eq!(state.get_position(), 0);
+        assert_eq!(state.get_char_count(), 0);
+        assert_eq!(state.get_word_count(), 0);
+        assert_eq!(state.get_typed_count(), 0);
+        assert_eq!(state.get_missed_count(), 0);
+        assert_eq!(state.get_duration(), 0);
+        assert!(state.get_keystrokes().is_empty());
+        assert!(state.get_samples().is_empty());
+    }
+
+    #[test]
+    fn test_get_samples() {
+        let mut state = State::new();
+        state.set_paragraph("some words".to_string());
+        type_text(&mut state, "some");
+        state.get_session_mut().sample(2000);
+        let seconds = state
+            .get_samples()
+            .iter()
+            .map(|sample| sample.second)
+            .collect::<Vec<i64>>();
+        assert_eq!(seconds, vec![1, 2]);
+        state.reset_stats();
+        assert!(state.get_samples().is_empty());
+    }
+
+    #[test]
+    fn test_get_keystrokes() {
+        let mut state = State::new();
+        state.set_paragraph("a".to_string());
+        let keystroke = Keystroke {
+            timestamp: 120,
+            expected: Some("a".to_string()),
+            typed: 'a',
+            is_correct: true,
+            ..Keystroke::default()
+        };
+        state.get_session_mut().type_char('a', 120);
+        assert_eq!(state.get_keystrokes(), &vec![keystroke]);
+        state.reset_stats();
+        assert!(state.get_keystrokes().is_empty());
+    }
+
+    #[test]
+    fn test_keystroke_counts() {
+        let mut state = State::new();
+        state.set_paragraph("some words here".to_string());
+        // a wrong and an extra letter, then a word cut short
+        type_text(&mut state, "sxmee wo here");
+        assert_eq!(state.get_correct_count(), 10);
+        assert_eq!(state.get_incorrect_count(), 1);
+        assert_eq!(state.get_extra_count(), 1);
+        assert_eq!(state.get_missed_count(), 3);
+        assert_eq!(state.get_typed_count(), 12);
+        assert_eq!(state.get_accuracy(), 10.0 / 15.0 * 100.0);
+    }
+
     #[test]
     fn test_set_get_timer() {
         let mut state = State::new();
@@ -181,17 +673,19 @@ mod tests {
     }
 
     #[test]
-    fn test_set_get_word_count() {
+    fn test_get_word_count() {
         let mut state = State::new();
-        state.set_word_count(50);
-        assert_eq!(state.get_word_count(), 50);
+        state.set_paragraph("some words here".to_string());
+        type_text(&mut state, "some words h");
+        assert_eq!(state.get_word_count(), 2);
     }
 
     #[test]
-    fn test_set_get_char_count() {
+    fn test_get_char_count() {
         let mut state = State::new();
-        state.set_char_count(200);
-        assert_eq!(state.get_char_count(), 200);
+        state.set_paragraph("some words here".to_string());
+        type_text(&mut state, "some wxrds");
+        assert_eq!(state.get_char_count(), 9);
     }
 
     #[test]
@@ -208,4 +702,159 @@ mod tests {
         state.set_reference_timestamp(None);
         assert!(state.get_reference_timestamp() > 0);
     }
+
+    #[test]
+    fn test_get_elapsed_millis() {
+        let mut state = State::new();
+        assert_eq!(state.get_elapsed_millis(), 0);
+        let clock = ManualClock::new(10_000);
+        state.set_clock(SharedClock::new(clock.clone()));
+        state.set_reference_timestamp(None);
+        assert_eq!(state.get_reference_timestamp(), 10_000);
+        clock.advance(2500);
+        assert_eq!(state.get_elapsed_millis(), 2500);
+        assert_eq!(state.get_elapsed_time(), 2);
+        state.set_reference_timestamp(Some(9_000));
+        assert_eq!(state.get_elapsed_millis(), 3500);
+    }
+
+    #[test]
+    fn test_set_get_config() {
+        let mut state = State::new();
+        assert_eq!(state.get_config(), &Config::default());
+        let config = Config {
+            countdown: 5,
+            ..Config::default()
+        };
+        state.set_config(config.clone());
+        assert_eq!(state.get_config(), &config);
+    }
+
+    #[test]
+    fn test_set_get_word_list() {
+        let mut state = State::new();
+        assert_eq!(state.get_word_list(), WORD_LIST);
+        assert_eq!(state.get_word_list_name(), DEFAULT_WORD_LIST_NAME);
+        state.set_word_list(Some(WordList {
+            name: "greek".to_string(),
+            words: "alpha\nbeta".to_string(),
+        }));
+        assert_eq!(state.get_word_list(), "alpha\nbeta");
+        assert_eq!(state.get_word_list_name(), "greek");
+    }
+
+    #[test]
+    fn test_set_get_word_lists() {
+        let mut state = State::new();
+        assert!(state.get_word_lists().is_empty());
+        let word_lists = vec![WordList {
+            name: "greek".to_string(),
+            words: "alpha\nbeta".to_string(),
+        }];
+        state.set_word_lists(word_lists.clone());
+        assert_eq!(state.get_word_lists(), &word_lists);
+    }
+
+    #[test]
+    fn test_set_get_seed() {
+        let mut state = State::new();
+        assert_eq!(state.get_seed(), None);
+        state.set_seed(Some(42));
+        assert_eq!(state.get_seed(), Some(42));
+        assert_eq!(state.get_paragraph_seed(), None);
+        state.set_paragraph_seed(Some(7));
+        assert_eq!(state.get_paragraph_seed(), Some(7));
+    }
+
+    #[test]
+    fn test_set_get_ghost() {
+        let mut state = State::new();
+        assert_eq!(state.get_ghost_target(), None);
+        state.set_ghost_target(Some(GhostTarget::Best));
+        assert_eq!(state.get_ghost_target(), Some(GhostTarget::Best));
+        assert_eq!(state.get_ghost(), &None);
+        let ghost = Ghost::new(Record::default(), &[], "some words");
+        state.set_ghost(Some(ghost.clone()));
+        assert_eq!(state.get_ghost(), &Some(ghost));
+    }
+
+    #[test]
+    fn test_set_get_race() {
+        let mut state = State::new();
+        assert_eq!(state.get_race_player_id(), None);
+        assert!(!state.get_is_race_host());
+        state.set_race_player_id(Some(0));
+        state.set_is_race_host(true);
+        assert_eq!(state.get_race_player_id(), Some(0));
+        assert!(state.get_is_race_host());
+        let players = vec![Player {
+            id: 0,
+            name: "ana".to_string(),
+            ..Player::default()
+        }];
+        state.set_race_players(players.clone());
+        assert_eq!(state.get_race_players(), &players);
+    }
+
+    #[test]
+    fn test_set_get_profiles() {
+        let mut state = State::new();
+        assert_eq!(state.get_profile(), &None);
+        let profiles = vec![Profile {
+            id: 1,
+            name: "ana".to_string(),
+        }];
+        state.set_profiles(profiles.clone());
+        state.set_profile(profiles.first().cloned());
+        state.set_profile_index(1);
+        state.set_profile_name("bo".to_string());
+        state.set_player_bests(vec![("ana".to_string(), 70)]);
+        assert_eq!(state.get_profiles(), &profiles);
+        assert_eq!(state.get_profile(), &profiles.first().cloned());
+        assert_eq!(state.get_profile_index(), 1);
+        assert_eq!(state.get_profile_name(), "bo");
+        assert_eq!(state.get_player_bests(), &vec![("ana".to_string(), 70)]);
+    }
+
+    #[test]
+    fn test_set_get_hot_seat() {
+        let mut state = State::new();
+        assert_eq!(state.get_hot_seat_size(), 0);
+        state.set_hot_seat_size(3);
+        assert_eq!(state.get_hot_seat_size(), 3);
+        assert_eq!(state.get_hot_seat(), &None);
+        state.set_hot_seat(Some(HotSeat::new(3, 7, Mode::Words(10))));
+        if let Some(hot_seat) = state.get_hot_seat_mut() {
+            hot_seat.seed = 8;
+        }
+        assert_eq!(state.get_hot_seat().as_ref().unwrap().seed, 8);
+    }
+
+    #[test]
+    fn test_set_get_quote() {
+        let mut state = State::new();
+        assert_eq!(state.get_quote(), &None);
+        let quote = Quote {
+            id: 6,
+            text: "Call me Ishmael.".to_string(),
+            source: "Herman Melville, Moby-Dick".to_string(),
+        };
+        state.set_quote(Some(quote.clone()));
+        assert_eq!(state.get_quote(), &Some(quote));
+
+        assert_eq!(state.get_quote_best(), None);
+        state.set_quote_best(Some(64));
+        assert_eq!(state.get_quote_best(), Some(64));
+    }
+
+    #[test]
+    fn test_set_get_punctuation_and_numbers() {
+        let mut state = State::new();
+        assert!(!state.get_punctuation());
+        assert!(!state.get_numbers());
+        state.set_punctuation(true);
+        state.set_numbers(true);
+        assert!(state.get_punctuation());
+        assert!(state.get_numbers());
+    }
 }
@@ -0,0 +1,401 @@
+use std::path::PathBuf;
+
+use crate::client::ClientError;
+use crate::constants::RecordColumn;
+use crate::keystroke::Keystroke;
+use crate::profile::Profile;
+use crate::record::Record;
+
+/// A page of records, the ones of a player or everyone's, sorted by a column.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct RecordQuery {
+    pub player_id: Option<i32>,
+    pub column: RecordColumn,
+    pub ascending: bool,
+    pub limit: usize,
+    pub offset: usize,
+}
+
+/// Where records, their keystrokes and the profiles are kept. `Client` saves them to the
+/// sqlite database, `MemoryStore` only for as long as the app runs.
+pub trait RecordStore {
+    /// Gets the store ready, the database is opened and migrated.
+    fn open(&mut self, app_config_path: PathBuf, db_name: &str) -> Result<(), ClientError>;
+    fn close(&mut self) -> Result<(), ClientError>;
+
+    fn get_records(&self) -> Result<Vec<Record>, ClientError>;
+    /// Returns the records saved under the player.
+    fn get_player_records(&self, player_id: i32) -> Result<Vec<Record>, ClientError>;
+    /// Returns a page of the records, ties are sorted by id in the same direction.
+    fn get_record_page(&self, query: &RecordQuery) -> Result<Vec<Record>, ClientError>;
+    /// Counts the records of the player, or of everyone.
+    fn count_records(&self, player_id: Option<i32>) -> Result<usize, ClientError>;
+    /// Saves the record and returns its id.
+    fn create_record(&mut self, record: &Record) -> Result<i64, ClientError>;
+    /// Removes the record together with its keystrokes.
+    fn delete_record(&mut self, record_id: i32) -> Result<(), ClientError>;
+    /// Returns the best wpm the player, or the guests, typed on the quote.
+    fn get_quote_best(
+        &self,
+        quote_id: i32,
+        player_id: Option<i32>,
+    ) -> Result<Option<i64>, ClientError>;
+
+    fn get_profiles(&self) -> Result<Vec<Profile>, ClientError>;
+    /// Saves a player and returns its profile, names are unique.
+    fn create_profile(&mut self, name: &str) -> Result<Profile, ClientError>;
+    /// Returns the best wpm of every player with records, fastest first.
+    fn get_player_bests(&self) -> Result<Vec<(String, i64)>, ClientError>;
+
+    fn create_keystrokes(
+        &mut self,
+        record_id: i64,
+        keystrokes: &[Keystroke],
+    ) -> Result<(), ClientError>;
+    fn get_keystrokes(&self, record_id: i32) -> Result<Vec<Keystroke>, ClientError>;
+}
+
+/// Sorts modes by kind and then by their number, "time 15" before "time 120", the same
+/// way the `Client` query does.
+fn mode_key(mode: &str) -> (&str, i64, &str) {
+    let (kind, value) = mode.split_once(' ').unwrap_or((mode, ""));
+    (kind, value.trim().parse().unwrap_or(0), mode)
+}
+
+/// Keeps everything in memory, for `--no-save` runs and tests.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    records: Vec<Record>,
+    /// Keystrokes paired with the id of their record.
+    keystrokes: Vec<(i32, Keystroke)>,
+    profiles: Vec<Profile>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        MemoryStore::default()
+    }
+}
+
+impl RecordStore for MemoryStore {
+    fn open(&mut self, _app_config_path: PathBuf, _db_name: &str) -> Result<(), ClientError> {
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<(), ClientError> {
+        Ok(())
+    }
+
+    fn get_records(&self) -> Result<Vec<Record>, ClientError> {
+        Ok(self.records.clone())
+    }
+
+    fn get_player_records(&self, player_id: i32) -> Result<Vec<Record>, ClientError> {
+        Ok(self
+            .records
+            .iter()
+            .filter(|record| record.player_id == Some(player_id))
+            .cloned()
+            .collect())
+    }
+
+    fn get_record_page(&self, query: &RecordQuery) -> Result<Vec<Record>, ClientError> {
+        let mut records = self
+            .records
+            .iter()
+            .filter(|record| query.player_id.is_none() || record.player_id == query.player_id)
+            .cloned()
+            .collect::<Vec<Record>>();
+        records.sort_by(|a, b| {
+            let ordering = match query.column {
+                RecordColumn::Date => a.date.cmp(&b.date),
+                RecordColumn::Mode => mode_key(&a.mode).cmp(&mode_key(&b.mode)),
+                RecordColumn::Wpm => a.wpm.cmp(&b.wpm),
+                RecordColumn::Accuracy => a.accuracy.total_cmp(&b.accuracy),
+                RecordColumn::Consistency => a.consistency.total_cmp(&b.consistency),
+            };
+            ordering.then(a.id.cmp(&b.id))
+        });
+        if !query.ascending {
+            records.reverse();
+        }
+        Ok(records
+            .into_iter()
+            .skip(query.offset)
+            .take(query.limit)
+            .collect())
+    }
+
+    fn count_records(&self, player_id: Option<i32>) -> Result<usize, ClientError> {
+        Ok(self
+            .records
+            .iter()
+            .filter(|record| player_id.is_none() || record.player_id == player_id)
+            .count())
+    }
+
+    fn create_record(&mut self, record: &Record) -> Result<i64, ClientError> {
+        // ids follow the highest one like sqlite rowids, deleting the newest record frees its id
+        let id = self
+            .records
+            .iter()
+            .map(|record| record.id)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        self.records.push(Record {
+            id,
+            ..record.clone()
+        });
+        Ok(id as i64)
+    }
+
+    fn delete_record(&mut self, record_id: i32) -> Result<(), ClientError> {
+        self.records.retain(|record| record.id != record_id);
+        self.keystrokes.retain(|(id, _)| *id != record_id);
+        Ok(())
+    }
+
+    fn get_quote_best(
+        &self,
+        quote_id: i32,
+        player_id: Option<i32>,
+    ) -> Result<Option<i64>, ClientError> {
+        Ok(self
+            .records
+            .iter()
+            .filter(|record| record.quote_id == Some(quote_id) && record.player_id == player_id)
+            .map(|record| record.wpm)
+            .max())
+    }
+
+    fn get_profiles(&self) -> Result<Vec<Profile>, ClientError> {
+        let mut profiles = self.profiles.clone();
+        profiles.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(profiles)
+    }
+
+    fn create_profile(&mut self, name: &str) -> Result<Profile, ClientError> {
+        if self.profiles.iter().any(|profile| profile.name == name) {
+            return Err(ClientError::ProfileExistsError(name.to_string()));
+        }
+        let profile = Profile {
+            id: self.profiles.len() as i32 + 1,
+            name: name.to_string(),
+        };
+        self.profiles.push(profile.clone());
+        Ok(profile)
+    }
+
+    fn get_player_bests(&self) -> Result<Vec<(String, i64)>, ClientError> {
+        let mut bests = self
+            .profiles
+            .iter()
+            .filter_map(|profile| {
+                self.records
+                    .iter()
+                    .filter(|record| record.player_id == Some(profile.id))
+                    .map(|record| record.wpm)
+                    .max()
+                    .map(|best| (profile.name.clone(), best))
+            })
+            .collect::<Vec<(String, i64)>>();
+        bests.sort_by(|(a_name, a_best), (b_name, b_best)| {
+            b_best.cmp(a_best).then_with(|| a_name.cmp(b_name))
+        });
+        Ok(bests)
+    }
+
+    fn create_keystrokes(
+        &mut self,
+        record_id: i64,
+        keystrokes: &[Keystroke],
+    ) -> Result<(), ClientError> {
+        let last_id = self
+            .keystrokes
+            .iter()
+            .map(|(_, keystroke)| keystroke.id)
+            .max()
+            .unwrap_or(0);
+        for (index, keystroke) in keystrokes.iter().enumerate() {
+            let id = last_id + index as i32 + 1;
+            self.keystrokes.push((
+                record_id as i32,
+                Keystroke {
+                    id,
+                    ..keystroke.clone()
+                },
+            ));
+        }
+        Ok(())
+    }
+
+    fn get_keystrokes(&self, record_id: i32) -> Result<Vec<Keystroke>, ClientError> {
+        Ok(self
+            .keystrokes
+            .iter()
+            .filter(|(id, _)| *id == record_id)
+            .map(|(_, keystroke)| keystroke.clone())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_store() {
+        let mut store = MemoryStore::new();
+        assert!(store.open(PathBuf::new(), "").is_ok());
+        assert!(store.get_records().unwrap().is_empty());
+
+        // RECORDS TEST
+        for (wpm, quote_id) in [(40, None), (55, Some(3)), (62, Some(3))] {
+            let record = Record {
+                wpm,
+                quote_id,
+                ..Record::default()
+            };
+            let result = store.create_record(&record);
+            assert!(result.is_ok());
+        }
+        let records = store.get_records().unwrap();
+        assert_eq!(
+            records.iter().map(|record| record.id).collect::<Vec<i32>>(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(store.get_quote_best(3, None).unwrap(), Some(62));
+        assert_eq!(store.get_quote_best(4, None).unwrap(), None);
+
+        // KEYSTROKES TEST
+        let keystroke = Keystroke {
+            timestamp: 120,
+            typed: 'a',
+            ..Keystroke::default()
+        };
+        let result = store.create_keystrokes(3, &[keystroke.clone(), keystroke]);
+        assert!(result.is_ok());
+        let keystrokes = store.get_keystrokes(3).unwrap();
+        assert_eq!(keystrokes.len(), 2);
+        assert_eq!(keystrokes[1].id, 2);
+        assert!(store.get_keystrokes(1).unwrap().is_empty());
+
+        // DELETE TEST
+        let result = store.delete_record(3);
+        assert!(result.is_ok());
+        assert_eq!(store.get_records().unwrap().len(), 2);
+        assert!(store.get_keystrokes(3).unwrap().is_empty());
+        // the id of the newest record is given out again, like sqlite does
+        assert_eq!(store.create_record(&Record::default()).unwrap(), 3);
+
+        // PLAYERS TEST
+        let bo = store.create_profile("bo").unwrap();
+        let ana = store.create_profile("ana").unwrap();
+        assert!(matches!(
+            store.create_profile("ana"),
+            Err(ClientError::ProfileExistsError(_))
+        ));
+        assert_eq!(store.get_profiles().unwrap(), vec![ana.clone(), bo.clone()]);
+        for (player_id, wpm) in [(ana.id, 50), (ana.id, 70), (bo.id, 80)] {
+            let record = Record {
+                wpm,
+                player_id: Some(player_id),
+                ..Record::default()
+            };
+            let result = store.create_record(&record);
+            assert!(result.is_ok());
+        }
+        assert_eq!(store.get_player_records(ana.id).unwrap().len(), 2);
+        let record = Record {
+            wpm: 30,
+            quote_id: Some(3),
+            player_id: Some(ana.id),
+            ..Record::default()
+        };
+        let record_id = store.create_record(&record).unwrap();
+        assert_eq!(store.get_quote_best(3, Some(ana.id)).unwrap(), Some(30));
+        assert_eq!(store.get_quote_best(3, Some(bo.id)).unwrap(), None);
+        assert_eq!(store.get_quote_best(3, None).unwrap(), Some(55));
+        let result = store.delete_record(record_id as i32);
+        assert!(result.is_ok());
+        assert_eq!(store.count_records(Some(ana.id)).unwrap(), 2);
+        assert_eq!(store.count_records(None).unwrap(), 6);
+
+        // PAGE TEST
+        let query = RecordQuery {
+            column: RecordColumn::Wpm,
+            limit: 2,
+            ..RecordQuery::default()
+        };
+        let page = store.get_record_page(&query).unwrap();
+        assert_eq!(
+            page.iter().map(|record| record.wpm).collect::<Vec<i64>>(),
+            vec![80, 70]
+        );
+        let query = RecordQuery { offset: 2, ..query };
+        let page = store.get_record_page(&query).unwrap();
+        assert_eq!(
+            page.iter().map(|record| record.wpm).collect::<Vec<i64>>(),
+            vec![55, 50]
+        );
+        let query = RecordQuery {
+            player_id: Some(ana.id),
+            ascending: true,
+            limit: 10,
+            offset: 0,
+            ..query
+        };
+        let page = store.get_record_page(&query).unwrap();
+        assert_eq!(
+            page.iter().map(|record| record.wpm).collect::<Vec<i64>>(),
+            vec![50, 70]
+        );
+        assert_eq!(
+            store.get_player_bests().unwrap(),
+            vec![("bo".to_string(), 80), ("ana".to_string(), 70)]
+        );
+        for mode in ["time 120", "words 10", "time 15"] {
+            let record = Record {
+                mode: mode.to_string(),
+                ..Record::default()
+            };
+            let result = store.create_record(&record);
+            assert!(result.is_ok());
+        }
+        let query = RecordQuery {
+            column: RecordColumn::Mode,
+            player_id: None,
+            ..query
+        };
+        let page = store.get_record_page(&query).unwrap();
+        assert_eq!(
+            page.iter()
+                .map(|record| record.mode.as_str())
+                .filter(|mode| !mode.is_empty())
+                .collect::<Vec<&str>>(),
+            vec!["time 15", "time 120", "words 10"]
+        );
+
+        assert!(store.close().is_ok());
+    }
+
+    #[test]
+    fn test_memory_store_ids() {
+        let mut store = MemoryStore::new();
+        for _ in 0..3 {
+            let record_id = store.create_record(&Record::default()).unwrap();
+            let result = store.create_keystrokes(record_id, &[Keystroke::default()]);
+            assert!(result.is_ok());
+        }
+
+        // ids past a deleted record keep counting from the highest one
+        let result = store.delete_record(1);
+        assert!(result.is_ok());
+        assert_eq!(store.create_record(&Record::default()).unwrap(), 4);
+        let result = store.create_keystrokes(4, &[Keystroke::default()]);
+        assert!(result.is_ok());
+        assert_eq!(store.get_keystrokes(4).unwrap()[0].id, 4);
+        assert_eq!(store.get_keystrokes(3).unwrap()[0].id, 3);
+    }
+}
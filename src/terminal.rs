@@ -0,0 +1,129 @@
+//! Puts the terminal into raw mode on the alternate screen and makes sure it's put back,
+//! whether the app quits, exits with an error or panics.
+
+use std::backtrace::Backtrace;
+use std::io::{stdout, Error};
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use crossterm::{
+    cursor::Show,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    ExecutableCommand,
+};
+
+use crate::constants::CRASH_REPORT_NAME;
+use crate::filesystem::{create_config_folder, write_file, FileSystemError};
+use crate::util::get_current_datetime;
+
+/// Keeps the terminal on the alternate screen in raw mode until it's dropped.
+pub struct TerminalGuard;
+
+impl TerminalGuard {
+    pub fn enter() -> Result<Self, Error> {
+        // created first so a failing step still restores what was already switched
+        let guard = TerminalGuard;
+        stdout().execute(EnterAlternateScreen)?;
+        enable_raw_mode()?;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = restore();
+    }
+}
+
+/// Leaves raw mode and the alternate screen and shows the cursor again.
+pub fn restore() -> Result<(), Error> {
+    let raw_mode = disable_raw_mode();
+    stdout().execute(LeaveAlternateScreen)?.execute(Show)?;
+    raw_mode
+}
+
+/// Restores the terminal before a panic is printed, so the message lands on the shell
+/// instead of the alternate screen, and saves a crash report with the backtrace. Only
+/// panics of the thread installing the hook end the app, the ones of race threads are
+/// printed as usual while the ui keeps running.
+pub fn install_panic_hook(app_config_path: PathBuf) {
+    let default_hook = panic::take_hook();
+    let main_thread = thread::current().id();
+    panic::set_hook(Box::new(move |info| {
+        if thread::current().id() != main_thread {
+            default_hook(info);
+            return;
+        }
+        let _ = restore();
+        default_hook(info);
+        report_crash(
+            &app_config_path,
+            &info.to_string(),
+            &Backtrace::force_capture().to_string(),
+        );
+    }));
+}
+
+/// Writes a crash report to the config folder and tells where on stderr.
+pub fn report_crash(app_config_path: &Path, message: &str, backtrace: &str) {
+    let report = get_crash_report(message, backtrace);
+    match write_crash_report(app_config_path, &report) {
+        Ok(path) => eprintln!("A crash report was written to {}", path.display()),
+        Err(e) => eprintln!("{e}"),
+    }
+}
+
+pub fn get_crash_report(message: &str, backtrace: &str) -> String {
+    let mut report = format!(
+        "typefast {} crashed on {}\n\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        get_current_datetime(),
+        message
+    );
+    if !backtrace.is_empty() {
+        report.push_str(&format!("\n{backtrace}\n"));
+    }
+    report
+}
+
+/// Saves the report in the config folder, replacing the last one, and returns its path.
+pub fn write_crash_report(
+    app_config_path: &Path,
+    report: &str,
+) -> Result<PathBuf, FileSystemError> {
+    create_config_folder(&app_config_path.to_path_buf())?;
+    let path = app_config_path.join(CRASH_REPORT_NAME);
+    write_file(&path, report)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::TEST_APP_PATH;
+    use std::fs;
+
+    #[test]
+    fn test_get_crash_report() {
+        let report = get_crash_report("panicked at src/view.rs:1:1", "");
+        assert!(report.starts_with(&format!("typefast {}", env!("CARGO_PKG_VERSION"))));
+        assert!(report.ends_with("\n\npanicked at src/view.rs:1:1\n"));
+
+        let report = get_crash_report("panicked", "0: main");
+        assert!(report.ends_with("panicked\n\n0: main\n"));
+    }
+
+    #[test]
+    fn test_write_crash_report() {
+        let app_config_path = Path::new(TEST_APP_PATH).join("crash");
+        let result = write_crash_report(&app_config_path, "report");
+        assert!(result.is_ok());
+        let path = result.unwrap();
+        assert_eq!(path, app_config_path.join(CRASH_REPORT_NAME));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "report");
+
+        let result = fs::remove_dir_all(app_config_path);
+        assert!(result.is_ok());
+    }
+}
@@ -1,20 +1,289 @@
-use chrono::Local;
+use chrono::{Datelike, Local, NaiveDate};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use unicode_segmentation::UnicodeSegmentation;
 
-pub fn calculate_word_speed(word_count: u64, duration: i64) -> i64 {
+use crate::constants::{
+    BACKSPACE_CHAR, BURST_WINDOW, CHARS_PER_WORD, DELETE_WORD_CHAR, NUMBER_RATE, PUNCTUATION_RATE,
+};
+use crate::keystroke::Keystroke;
+use crate::sample::Sample;
+
+/// Standard words per minute over a duration in milliseconds, where every
+/// `CHARS_PER_WORD` typed characters count as a word.
+pub fn calculate_word_speed(char_count: u64, duration: i64) -> i64 {
     if duration == 0 {
         return 0_i64;
     }
     let duration = duration as f64;
-    let word_count = word_count as f64;
-    (word_count / (duration / 60.0)).round() as i64
+    let word_count = char_count as f64 / CHARS_PER_WORD;
+    (word_count / (duration / 60_000.0)).round() as i64
 }
+/// Characters per minute over a duration in milliseconds.
 pub fn calculate_char_speed(char_count: u64, duration: i64) -> i64 {
     if duration == 0 {
         return 0_i64;
     }
     let duration = duration as f64;
     let char_count = char_count as f64;
-    (char_count / (duration / 60.0)).round() as i64
+    (char_count / (duration / 60_000.0)).round() as i64
+}
+
+/// Spaces and line breaks end words, indentation is the spaces after a line break.
+pub fn is_separator(character: char) -> bool {
+    character == ' ' || character == '\n'
+}
+
+/// Splits text into words on runs of spaces and line breaks. Like `str::split` it keeps
+/// an empty last word after a trailing separator, that's the word being started.
+pub fn split_words(text: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut word_start = Some(0);
+    for (index, character) in text.char_indices() {
+        match word_start {
+            Some(start) if is_separator(character) => {
+                words.push(&text[start..index]);
+                word_start = None;
+            }
+            None if !is_separator(character) => word_start = Some(index),
+            _ => {}
+        }
+    }
+    words.push(&text[word_start.unwrap_or(text.len())..]);
+    words
+}
+
+/// Returns the runs of spaces and line breaks between the words of the text, a line
+/// break run carries the indentation of the next line.
+pub fn split_separators(text: &str) -> Vec<&str> {
+    text.split(|character: char| !is_separator(character))
+        .filter(|separator| !separator.is_empty())
+        .collect()
+}
+
+/// Returns the grapheme index in the paragraph where the next input will land.
+pub fn get_caret_position(paragraph: &str, input: &str) -> usize {
+    let typed_words = split_words(input);
+    let current_index = typed_words.len() - 1;
+    let separators = split_separators(paragraph);
+
+    split_words(paragraph)
+        .iter()
+        .zip(typed_words.iter())
+        .enumerate()
+        .map(|(index, (word, typed_word))| {
+            let word_length = word.graphemes(true).count();
+            if index < current_index {
+                let separator = separators.get(index).copied().unwrap_or_default();
+                word_length + separator.graphemes(true).count()
+            } else {
+                word_length.min(typed_word.graphemes(true).count())
+            }
+        })
+        .sum()
+}
+
+pub fn calculate_accuracy(correct_count: u64, total_count: u64) -> f64 {
+    if total_count == 0 {
+        return 0.0;
+    }
+    correct_count as f64 / total_count as f64 * 100.0
+}
+
+/// Counts the typed graphemes that match the paragraph, comparing word by word
+/// so a skipped or overlong word doesn't shift the rest of the input.
+/// The separators that finish words are counted as one correct character each.
+pub fn count_correct_chars(paragraph: &str, input: &str) -> u64 {
+    let typed_words = split_words(input);
+    let correct_chars = split_words(paragraph)
+        .into_iter()
+        .zip(typed_words.iter())
+        .map(|(word, typed_word)| {
+            word.graphemes(true)
+                .zip(typed_word.graphemes(true))
+                .filter(|(expected, typed)| expected == typed)
+                .count() as u64
+        })
+        .sum::<u64>();
+    correct_chars + typed_words.len() as u64 - 1
+}
+
+/// Returns the paragraph word under the caret together with what was typed for it so far.
+pub fn get_current_word<'a>(paragraph: &'a str, input: &'a str) -> (&'a str, &'a str) {
+    let typed_words = split_words(input);
+    let word = split_words(paragraph)
+        .get(typed_words.len() - 1)
+        .copied()
+        .unwrap_or_default();
+    (word, typed_words.last().copied().unwrap_or_default())
+}
+
+/// A paragraph is finished once its last word has been typed to full length.
+pub fn is_paragraph_finished(paragraph: &str, input: &str) -> bool {
+    let (word, typed_word) = get_current_word(paragraph, input);
+    split_words(input).len() == split_words(paragraph).len()
+        && typed_word.graphemes(true).count() >= word.graphemes(true).count()
+}
+
+/// Builds the sample that closes `second`, the net wpm is passed in because it
+/// depends on the input at that moment rather than on the keystrokes alone.
+pub fn get_sample(keystrokes: &[Keystroke], second: i64, wpm: i64) -> Sample {
+    let window = (second - 1) * 1000..second * 1000;
+    let (typed_count, errors) = keystrokes
+        .iter()
+        .filter(|keystroke| window.contains(&keystroke.timestamp))
+        .filter(|keystroke| ![BACKSPACE_CHAR, DELETE_WORD_CHAR].contains(&keystroke.typed))
+        .fold((0, 0), |(typed_count, errors), keystroke| {
+            (typed_count + 1, errors + u64::from(!keystroke.is_correct))
+        });
+
+    Sample {
+        second,
+        wpm,
+        raw_wpm: calculate_word_speed(typed_count, 1000),
+        errors,
+    }
+}
+
+/// Breaks words into lines no wider than `width` and returns the index of the first
+/// word on every line. The widths include the space after each word, a word that
+/// doesn't fit on a line on its own still gets a line to itself. `line_breaks` holds
+/// how many line breaks follow each word, every one after the first adds an empty line.
+pub fn get_line_starts(word_widths: &[usize], line_breaks: &[usize], width: usize) -> Vec<usize> {
+    let mut line_starts = vec![0];
+    let mut line_width = 0;
+    for (index, word_width) in word_widths.iter().enumerate() {
+        let break_count = index
+            .checked_sub(1)
+            .and_then(|previous| line_breaks.get(previous))
+            .copied()
+            .unwrap_or_default();
+        if break_count > 0 {
+            line_starts.extend(std::iter::repeat_n(index, break_count));
+            line_width = 0;
+        } else if line_width > 0 && line_width + word_width > width {
+            line_starts.push(index);
+            line_width = 0;
+        }
+        line_width += word_width;
+    }
+    line_starts
+}
+
+/// Maps the coefficient of variation of the per second raw speed to 0-100%,
+/// using the same curve as monkeytype so a perfectly steady run scores 100%.
+pub fn calculate_consistency(samples: &[Sample]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let count = samples.len() as f64;
+    let mean = samples
+        .iter()
+        .map(|sample| sample.raw_wpm as f64)
+        .sum::<f64>()
+        / count;
+    if mean == 0.0 {
+        return 0.0;
+    }
+    let variance = samples
+        .iter()
+        .map(|sample| (sample.raw_wpm as f64 - mean).powi(2))
+        .sum::<f64>()
+        / count;
+    let variation = variance.sqrt() / mean;
+
+    100.0 * (1.0 - (variation + variation.powi(3) / 3.0 + variation.powi(5) / 5.0).tanh())
+}
+
+/// The best raw speed averaged over `BURST_WINDOW` consecutive seconds.
+pub fn calculate_burst_speed(samples: &[Sample]) -> i64 {
+    let window = BURST_WINDOW.min(samples.len()).max(1);
+    samples
+        .windows(window)
+        .map(|window_samples| {
+            window_samples
+                .iter()
+                .map(|sample| sample.raw_wpm)
+                .sum::<i64>()
+                / window_samples.len() as i64
+        })
+        .max()
+        .unwrap_or_default()
+}
+
+/// Returns a random generator, seeded ones always give the same sequence.
+pub fn get_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// Replaces some of the words with numbers of one to four digits.
+pub fn add_numbers(words: Vec<String>, rng: &mut StdRng) -> Vec<String> {
+    words
+        .into_iter()
+        .map(|word| {
+            if rng.gen_bool(NUMBER_RATE) {
+                let digits = rng.gen_range(1..=4);
+                rng.gen_range(10_u32.pow(digits - 1)..10_u32.pow(digits))
+                    .to_string()
+            } else {
+                word
+            }
+        })
+        .collect()
+}
+
+/// Splits the words into sentences: capitalizes sentence starts, ends the last word with
+/// a period and sprinkles commas, quotes, brackets and other marks in between.
+pub fn add_punctuation(words: Vec<String>, rng: &mut StdRng) -> Vec<String> {
+    let last_index = words.len().saturating_sub(1);
+    let mut is_sentence_start = true;
+    words
+        .into_iter()
+        .enumerate()
+        .map(|(index, word)| {
+            let word = if is_sentence_start {
+                capitalize(&word)
+            } else {
+                word
+            };
+            is_sentence_start = false;
+            if index == last_index {
+                return format!("{word}.");
+            }
+            if !rng.gen_bool(PUNCTUATION_RATE) {
+                return word;
+            }
+            match rng.gen_range(0..10) {
+                0..=3 => format!("{word},"),
+                4 | 5 => {
+                    is_sentence_start = true;
+                    format!("{word}.")
+                }
+                6 => {
+                    is_sentence_start = true;
+                    format!("{word}{}", ['?', '!'].choose(rng).unwrap_or(&'?'))
+                }
+                7 => format!("\"{word}\""),
+                8 => format!("({word})"),
+                _ => format!("{word}{}", [':', ';'].choose(rng).unwrap_or(&';')),
+            }
+        })
+        .collect()
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Seed of the daily challenge, the date written as a number like 20240131.
+pub fn get_daily_seed(date: NaiveDate) -> u64 {
+    date.year() as u64 * 10000 + date.month() as u64 * 100 + date.day() as u64
 }
 
 pub fn get_current_datetime() -> String {
@@ -28,21 +297,259 @@ mod tests {
 
     #[test]
     fn test_calculate_word_speed() {
-        assert_eq!(calculate_word_speed(120, 60), 120);
-        assert_eq!(calculate_word_speed(0, 60), 0);
-        assert_eq!(calculate_word_speed(120, 0), 0);
-        assert_eq!(calculate_word_speed(60, 120), 30);
+        assert_eq!(calculate_word_speed(600, 60_000), 120);
+        assert_eq!(calculate_word_speed(0, 60_000), 0);
+        assert_eq!(calculate_word_speed(600, 0), 0);
+        assert_eq!(calculate_word_speed(300, 120_000), 30);
+        assert_eq!(calculate_word_speed(52, 15_000), 42);
+        // fractions of a second count
+        assert_eq!(calculate_word_speed(50, 7_500), 80);
+        assert_eq!(calculate_word_speed(50, 7_999), 75);
     }
 
     #[test]
     fn test_calculate_char_speed() {
-        assert_eq!(calculate_char_speed(600, 60), 600);
-        assert_eq!(calculate_char_speed(0, 60), 0);
+        assert_eq!(calculate_char_speed(600, 60_000), 600);
+        assert_eq!(calculate_char_speed(0, 60_000), 0);
         assert_eq!(calculate_char_speed(600, 0), 0);
-        assert_eq!(calculate_char_speed(300, 120), 150);
+        assert_eq!(calculate_char_speed(300, 120_000), 150);
+        assert_eq!(calculate_char_speed(10, 1_500), 400);
+    }
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(split_words(""), vec![""]);
+        assert_eq!(split_words("some words"), vec!["some", "words"]);
+        assert_eq!(split_words("some "), vec!["some", ""]);
+        assert_eq!(
+            split_words("fn main() {\n    x\n}"),
+            vec!["fn", "main()", "{", "x", "}"]
+        );
+        assert_eq!(split_words("a\n\n  b"), vec!["a", "b"]);
+        assert_eq!(split_words("a\n  "), vec!["a", ""]);
+    }
+
+    #[test]
+    fn test_split_separators() {
+        assert!(split_separators("word").is_empty());
+        assert_eq!(split_separators("some words"), vec![" "]);
+        assert_eq!(
+            split_separators("if x {\n    y\n\n}"),
+            vec![" ", " ", "\n    ", "\n\n"]
+        );
+    }
+
+    #[test]
+    fn test_count_correct_chars() {
+        assert_eq!(count_correct_chars("some words", ""), 0);
+        assert_eq!(count_correct_chars("some words", "some"), 4);
+        assert_eq!(count_correct_chars("some words", "sxme wo"), 6);
+        // skipped chars don't shift the next word
+        assert_eq!(count_correct_chars("some words", "so words"), 8);
+        // extra chars don't shift the next word either
+        assert_eq!(count_correct_chars("some words", "someee words"), 10);
+        assert_eq!(count_correct_chars("héllo wörld", "hello wörld"), 10);
+        assert_eq!(count_correct_chars("日本語 👍🏽", "日本話 👍"), 3);
+        // a line break and its indentation count as a single character
+        assert_eq!(count_correct_chars("{\n    x\n}", "{\n    x"), 3);
+    }
+
+    #[test]
+    fn test_calculate_accuracy() {
+        assert_eq!(calculate_accuracy(0, 0), 0.0);
+        assert_eq!(calculate_accuracy(50, 50), 100.0);
+        assert_eq!(calculate_accuracy(45, 60), 75.0);
+    }
+
+    #[test]
+    fn test_get_current_word() {
+        assert_eq!(get_current_word("some words", ""), ("some", ""));
+        assert_eq!(get_current_word("some words", "sx"), ("some", "sx"));
+        assert_eq!(get_current_word("some words", "some w"), ("words", "w"));
+        assert_eq!(get_current_word("some", "some "), ("", ""));
+    }
+
+    #[test]
+    fn test_get_caret_position() {
+        assert_eq!(get_caret_position("some words", ""), 0);
+        assert_eq!(get_caret_position("some words", "so"), 2);
+        assert_eq!(get_caret_position("some words", "sxme "), 5);
+        assert_eq!(get_caret_position("some words", "so w"), 6);
+        assert_eq!(get_caret_position("some words", "someee"), 4);
+        assert_eq!(get_caret_position("héllo wörld", "héllo wö"), 8);
+        // a decomposed é is two chars but a single grapheme
+        assert_eq!(get_caret_position("he\u{301}llo wörld", "he\u{301}l"), 3);
+        assert_eq!(get_caret_position("日本語 👍🏽 ok", "日本語 👍🏽 "), 6);
+        // indentation is passed over with the line break
+        assert_eq!(get_caret_position("{\n    x\n}", "{\n"), 6);
+        assert_eq!(get_caret_position("{\n    x\n}", "{\n    x"), 7);
+    }
+
+    #[test]
+    fn test_is_paragraph_finished() {
+        assert!(!is_paragraph_finished("some words", ""));
+        assert!(!is_paragraph_finished("some words", "some"));
+        assert!(!is_paragraph_finished("some words", "some wor"));
+        assert!(is_paragraph_finished("some words", "some words"));
+        assert!(is_paragraph_finished("some words", "some wordz"));
+        assert!(!is_paragraph_finished("日本語 👍🏽", "日本語 "));
+        assert!(is_paragraph_finished("日本語 👍🏽", "日本語 👍🏽"));
+        assert!(!is_paragraph_finished("{\n    x\n}", "{\n    x"));
+        assert!(is_paragraph_finished("{\n    x\n}", "{\n    x\n}"));
+    }
+
+    #[test]
+    fn test_get_sample() {
+        let keystroke = |timestamp: i64, typed: char, is_correct: bool| Keystroke {
+            timestamp,
+            typed,
+            is_correct,
+            ..Keystroke::default()
+        };
+        let keystrokes = vec![
+            keystroke(100, 'a', true),
+            keystroke(900, 'b', false),
+            keystroke(1000, BACKSPACE_CHAR, false),
+            keystroke(1200, 'c', true),
+            keystroke(1999, 'd', true),
+        ];
+
+        let sample = get_sample(&keystrokes, 1, 24);
+        assert_eq!(
+            sample,
+            Sample {
+                second: 1,
+                wpm: 24,
+                raw_wpm: 24,
+                errors: 1
+            }
+        );
+        let sample = get_sample(&keystrokes, 2, 30);
+        assert_eq!(sample.raw_wpm, 24);
+        assert_eq!(sample.errors, 0);
+        let sample = get_sample(&keystrokes, 3, 20);
+        assert_eq!(sample.raw_wpm, 0);
+    }
+
+    #[test]
+    fn test_get_line_starts() {
+        assert_eq!(get_line_starts(&[], &[], 10), vec![0]);
+        assert_eq!(get_line_starts(&[5, 5, 4], &[], 10), vec![0, 2]);
+        assert_eq!(get_line_starts(&[5, 5, 5, 4], &[], 10), vec![0, 2]);
+        assert_eq!(get_line_starts(&[5, 6, 5, 4], &[], 10), vec![0, 1, 2]);
+        assert_eq!(get_line_starts(&[12, 3], &[], 10), vec![0, 1]);
+        // line breaks end a line even when the next word fits
+        assert_eq!(get_line_starts(&[3, 3, 3, 3], &[1, 0, 0], 10), vec![0, 1]);
+        assert_eq!(
+            get_line_starts(&[2, 2, 2, 2], &[0, 1, 1], 10),
+            vec![0, 2, 3]
+        );
+        // a blank line is an empty line between the two words
+        assert_eq!(get_line_starts(&[2, 2], &[2], 10), vec![0, 1, 1]);
+    }
+
+    fn get_test_samples(raw_speeds: &[i64]) -> Vec<Sample> {
+        raw_speeds
+            .iter()
+            .enumerate()
+            .map(|(index, raw_wpm)| Sample {
+                second: index as i64 + 1,
+                raw_wpm: *raw_wpm,
+                ..Sample::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_calculate_consistency() {
+        assert_eq!(calculate_consistency(&[]), 0.0);
+        assert_eq!(calculate_consistency(&get_test_samples(&[0, 0])), 0.0);
+        assert_eq!(
+            calculate_consistency(&get_test_samples(&[60, 60, 60])),
+            100.0
+        );
+        let steady = calculate_consistency(&get_test_samples(&[58, 62, 60, 61]));
+        let uneven = calculate_consistency(&get_test_samples(&[20, 100, 30, 90]));
+        assert!(steady > 90.0);
+        assert!(uneven < 60.0);
+        assert!(uneven > 0.0);
+    }
+
+    #[test]
+    fn test_calculate_burst_speed() {
+        assert_eq!(calculate_burst_speed(&[]), 0);
+        assert_eq!(calculate_burst_speed(&get_test_samples(&[48])), 48);
+        assert_eq!(
+            calculate_burst_speed(&get_test_samples(&[40, 60, 90, 90, 30])),
+            80
+        );
+    }
+
+    #[test]
+    fn test_get_daily_seed() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+        assert_eq!(get_daily_seed(date), 20240131);
+        assert_ne!(
+            get_daily_seed(date),
+            get_daily_seed(date.succ_opt().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_get_rng() {
+        let numbers = get_rng(Some(7)).gen::<[u64; 4]>();
+        assert_eq!(numbers, get_rng(Some(7)).gen::<[u64; 4]>());
+        assert_ne!(numbers, get_rng(Some(8)).gen::<[u64; 4]>());
+    }
+
+    #[test]
+    fn test_add_numbers() {
+        let words = vec!["word".to_string(); 1000];
+        let result = add_numbers(words.clone(), &mut get_rng(Some(1)));
+        assert_eq!(result.len(), 1000);
+        assert_eq!(result, add_numbers(words, &mut get_rng(Some(1))));
+
+        let numbers = result
+            .iter()
+            .filter(|word| *word != "word")
+            .collect::<Vec<&String>>();
+        assert!(numbers.len() > 50 && numbers.len() < 150);
+        assert!(numbers
+            .iter()
+            .all(|number| number.len() <= 4 && number.parse::<u32>().is_ok()));
+        assert!(numbers.iter().any(|number| number.len() == 1));
+    }
+
+    #[test]
+    fn test_add_punctuation() {
+        assert!(add_punctuation(Vec::new(), &mut get_rng(Some(1))).is_empty());
+        assert_eq!(
+            add_punctuation(vec!["één".to_string()], &mut get_rng(Some(1))),
+            vec!["Één."]
+        );
+
+        let words = vec!["word".to_string(); 500];
+        let result = add_punctuation(words, &mut get_rng(Some(2)));
+        assert_eq!(result.len(), 500);
+        assert!(result[0].starts_with("Word"));
+        assert_eq!(result.last().unwrap(), "word.");
+        assert!(result.iter().any(|word| word.ends_with(',')));
+        assert!(result.iter().any(|word| word.starts_with('(')));
+        assert!(result.iter().any(|word| word.starts_with('"')));
+        for (index, word) in result.iter().enumerate().skip(1) {
+            let is_sentence_start = result[index - 1].ends_with(['.', '?', '!']);
+            assert_eq!(word.contains('W'), is_sentence_start);
+            // marks are only added around the word
+            let letters = word
+                .chars()
+                .filter(|character| character.is_alphabetic())
+                .collect::<String>();
+            assert_eq!(letters.to_lowercase(), "word");
+        }
     }
 
     #[test]
+    #[allow(clippy::len_zero)]
     fn test_get_current_datetime() {
         let datetime = get_current_datetime();
         assert!(datetime.len() > 0);
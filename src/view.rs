@@ -3,16 +3,25 @@ use std::{io::Error as StandardError, rc::Rc};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     prelude::{Backend, Terminal},
-    style::{Color, Style},
+    style::{Color, Modifier, Style, Stylize},
+    symbols::Marker,
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Padding, Paragraph, Wrap},
+    widgets::{
+        block::Title, Axis, Block, Borders, Chart, Dataset, GraphType, List, ListItem, Padding,
+        Paragraph, Row, Table, TableState, Wrap,
+    },
     Frame,
 };
 use thiserror::Error;
+use unicode_segmentation::UnicodeSegmentation;
 
-use crate::constants::{Page, COUNTDOWN_DURATION, GAME_DURATION, MENU_ITEMS};
+use crate::config::Config;
+use crate::constants::{
+    Mode, Page, GUEST_NAME, MENU_ITEMS, RACE_BAR_WIDTH, RACE_PANEL_WIDTH, RECORDS_PAGE_SIZE,
+    RECORD_COLUMNS, VIEWPORT_LINES,
+};
 use crate::state::State;
-use crate::util::{calculate_char_speed, calculate_word_speed};
+use crate::util::{calculate_word_speed, get_line_starts, split_separators, split_words};
 
 #[derive(Debug, Default)]
 pub struct View {}
@@ -35,6 +44,8 @@ impl View {
                     Page::CountDown => View::draw_countdown_page(frame, area, state),
                     Page::Records => View::draw_records_page(frame, area, state),
                     Page::GameResult => View::draw_game_result_page(frame, area, state),
+                    Page::Profiles => View::draw_profiles_page(frame, area, state),
+                    Page::Leaderboard => View::draw_leaderboard_page(frame, area, state),
                 }
             })
             .map_err(|e| ViewError::DrawError(state.page, e))
@@ -61,44 +72,276 @@ impl View {
         let outer_layout = chunks.0;
         let inner_layout = chunks.1;
 
-        let message = state.get_paragraph();
-        let message_length = message.len();
-        let position = state.get_position() as usize;
         let elapsed_time = state.get_elapsed_time();
-        let timer = GAME_DURATION - elapsed_time;
-        let word_speed = calculate_word_speed(state.word_count, elapsed_time);
-        let char_speed = calculate_char_speed(state.char_count, elapsed_time);
-
-        let lines = Line::from(vec![
-            Span::styled(&message[0..position], Style::default().fg(Color::Green)),
-            Span::styled(
-                &message[position..message_length],
-                Style::default().fg(Color::Rgb(21, 21, 21)),
+        let progress = match state.get_mode() {
+            Mode::Time(seconds) => format!("timer: {}s", seconds - elapsed_time),
+            Mode::Words(word_count) => format!(
+                "words: {}/{}, time: {}s",
+                state.get_word_count(),
+                word_count,
+                elapsed_time
+            ),
+            Mode::Quote(_) | Mode::Code(_) | Mode::Daily => format!(
+                "words: {}/{}, time: {}s",
+                state.get_word_count(),
+                split_words(state.get_paragraph()).len(),
+                elapsed_time
             ),
-        ]);
+        };
+        let elapsed_millis = state.get_elapsed_millis();
+        let word_speed = calculate_word_speed(state.get_char_count(), elapsed_millis);
+        let raw_word_speed = calculate_word_speed(state.get_typed_count(), elapsed_millis);
+
+        // the other players of a race line up beside the paragraph
+        let game_area = match state.get_race_players().is_empty() {
+            true => outer_layout[0],
+            false => {
+                let race_layout = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Min(0), Constraint::Length(RACE_PANEL_WIDTH)])
+                    .split(outer_layout[0]);
+                View::draw_race_players(frame, state, race_layout[1]);
+                race_layout[0]
+            }
+        };
         let title = Line::from(" typefast ");
-        let widget = Paragraph::new(lines)
-            .alignment(Alignment::Left)
-            .block(
-                Block::bordered()
-                    .title(title.centered())
-                    .padding(Padding::new(3, 3, 1, 1)),
-            )
-            .wrap(Wrap { trim: true });
+        let block = Block::bordered()
+            .title(title.centered())
+            .padding(Padding::new(3, 3, 1, 1));
+        let paragraph_area = block.inner(game_area);
+        let ghost_position = state
+            .get_ghost()
+            .as_ref()
+            .map(|ghost| ghost.get_position(elapsed_millis));
+        let word_spans = View::get_word_spans(
+            state.get_paragraph(),
+            state.get_input(),
+            state.get_config(),
+            ghost_position,
+        );
+        let word_widths = word_spans
+            .iter()
+            .map(|spans| spans.iter().map(|span| span.width()).sum())
+            .collect::<Vec<usize>>();
+        let line_breaks = split_separators(state.get_paragraph())
+            .iter()
+            .map(|separator| separator.matches('\n').count())
+            .collect::<Vec<usize>>();
+        let line_starts =
+            get_line_starts(&word_widths, &line_breaks, paragraph_area.width as usize);
 
-        frame.render_widget(widget, outer_layout[0]);
-        View::draw_legend(
-            frame,
-            format!(
-                "esc: Exit, timer: {}s wpm: {}, cpm: {}",
-                timer, word_speed, char_speed
-            )
-            .as_str(),
-            inner_layout[0],
+        // keep the active line second from the top once the first line is done
+        let current_index = split_words(state.get_input()).len() - 1;
+        let active_line = line_starts.partition_point(|start| *start <= current_index) - 1;
+        let first_line = active_line.saturating_sub(1);
+        let mut word_spans = word_spans.into_iter();
+        let lines = line_starts
+            .iter()
+            .enumerate()
+            .map(|(line_index, line_start)| {
+                let line_end = line_starts
+                    .get(line_index + 1)
+                    .copied()
+                    .unwrap_or(word_widths.len());
+                Line::from(
+                    word_spans
+                        .by_ref()
+                        .take(line_end - line_start)
+                        .flatten()
+                        .collect::<Vec<Span>>(),
+                )
+            })
+            .skip(first_line)
+            .take(VIEWPORT_LINES)
+            .collect::<Vec<Line>>();
+        let widget = Paragraph::new(lines).alignment(Alignment::Left);
+
+        frame.render_widget(block, game_area);
+        frame.render_widget(widget, paragraph_area);
+        let mut legend = format!(
+            "esc: Exit, {} wpm: {}, raw: {}, acc: {:.0}%",
+            progress,
+            word_speed,
+            raw_word_speed,
+            state.get_accuracy()
         );
+        // the gap gets its own line, the legend is only half as wide as the page
+        if let Some(ghost) = state.get_ghost() {
+            legend += "\n";
+            legend += &ghost.get_gap(state.get_position() as usize, elapsed_millis);
+        }
+        View::draw_legend(frame, legend.as_str(), inner_layout[0]);
         View::draw_error(frame, state, inner_layout[1]);
     }
 
+    /// Lists the players of the race with a bar of how much of the paragraph they typed.
+    fn draw_race_players(frame: &mut Frame, state: &State, area: Rect) {
+        let paragraph_length = state.get_paragraph().graphemes(true).count();
+        let lines = state
+            .get_race_players()
+            .iter()
+            .flat_map(|player| {
+                let style = match Some(player.id) == state.get_race_player_id() {
+                    true => Style::default().add_modifier(Modifier::BOLD),
+                    false => Style::default(),
+                };
+                let name = match player.progress.finished {
+                    true => format!("{} ✓", player.name),
+                    false => player.name.clone(),
+                };
+                [
+                    Line::styled(name, style),
+                    Line::styled(
+                        format!(
+                            "{} {} wpm",
+                            View::get_progress_bar(player.progress.position, paragraph_length),
+                            player.progress.wpm
+                        ),
+                        style,
+                    ),
+                ]
+            })
+            .collect::<Vec<Line>>();
+        let widget = Paragraph::new(lines).block(
+            Block::bordered()
+                .title(" race ")
+                .padding(Padding::new(1, 1, 1, 1)),
+        );
+        frame.render_widget(widget, area);
+    }
+
+    /// Fills a bar of `RACE_BAR_WIDTH` cells by how far the position is into the paragraph.
+    fn get_progress_bar(position: usize, paragraph_length: usize) -> String {
+        let filled = match paragraph_length {
+            0 => 0,
+            _ => (position * RACE_BAR_WIDTH / paragraph_length).min(RACE_BAR_WIDTH),
+        };
+        "█".repeat(filled) + &"░".repeat(RACE_BAR_WIDTH - filled)
+    }
+
+    /// Styles the paragraph word by word, every word carries its extra characters and
+    /// the separator that follows it so a word never gets separated from them by a line
+    /// break. Line breaks show as `↵` and the indentation after them leads the next word.
+    /// The ghost's caret is drawn as a background at its paragraph position.
+    fn get_word_spans<'a>(
+        paragraph: &'a str,
+        input: &'a str,
+        config: &Config,
+        ghost_position: Option<usize>,
+    ) -> Vec<Vec<Span<'a>>> {
+        let theme = config.theme;
+        let correct_style = Style::default().fg(theme.correct);
+        let incorrect_style = Style::default().fg(theme.incorrect);
+        let extra_style = Style::default().fg(theme.extra);
+        let missed_style = Style::default()
+            .fg(theme.missed)
+            .add_modifier(Modifier::DIM);
+        let untyped_style = Style::default().fg(theme.untyped);
+        let caret_style = untyped_style.add_modifier(config.caret.get_modifier());
+
+        let words = split_words(paragraph);
+        let separators = split_separators(paragraph);
+        let typed_words = split_words(input);
+        let typed_separators = split_separators(input);
+        let current_index = typed_words.len() - 1;
+        let mut paragraph_index = 0;
+        let mut with_ghost = |style: Style, length: usize| {
+            let is_ghost = ghost_position.is_some_and(|position| {
+                (paragraph_index..paragraph_index + length).contains(&position)
+            });
+            paragraph_index += length;
+            match is_ghost {
+                true => style.bg(theme.ghost),
+                false => style,
+            }
+        };
+
+        words
+            .iter()
+            .enumerate()
+            .map(|(index, word)| {
+                let mut spans = Vec::new();
+                let mut is_indenting = false;
+                if let Some(separator) = index.checked_sub(1).and_then(|i| separators.get(i)) {
+                    let indentation_start =
+                        separator.rfind('\n').map_or(separator.len(), |i| i + 1);
+                    // only a separator typed the way the paragraph expects continues
+                    let typed_length = typed_separators
+                        .get(index - 1)
+                        .filter(|typed| separator.starts_with(**typed))
+                        .map_or(0, |typed| typed.len());
+                    for position in indentation_start..separator.len() {
+                        let style = if position < typed_length {
+                            correct_style
+                        } else if index == current_index && position == typed_length {
+                            is_indenting = true;
+                            caret_style
+                        } else {
+                            untyped_style
+                        };
+                        spans.push(Span::styled(
+                            &separator[position..position + 1],
+                            with_ghost(style, 1),
+                        ));
+                    }
+                }
+
+                let typed_word = typed_words.get(index).copied().unwrap_or_default();
+                let typed_graphemes = typed_word.graphemes(true).collect::<Vec<&str>>();
+                let typed_length = typed_graphemes.len();
+                let mut word_length = 0;
+                for (grapheme_index, expected) in word.graphemes(true).enumerate() {
+                    word_length += 1;
+                    // a grapheme still being composed counts as correct while it is a prefix
+                    let is_composing = index == current_index && grapheme_index + 1 == typed_length;
+                    let style = match typed_graphemes.get(grapheme_index) {
+                        Some(typed) if *typed == expected => correct_style,
+                        Some(typed) if is_composing && expected.starts_with(typed) => correct_style,
+                        Some(_) => incorrect_style,
+                        None if index < current_index => missed_style,
+                        None if index == current_index
+                            && grapheme_index == typed_length
+                            && !is_indenting =>
+                        {
+                            caret_style
+                        }
+                        None => untyped_style,
+                    };
+                    spans.push(Span::styled(expected, with_ghost(style, 1)));
+                }
+                let extra_offset = typed_graphemes
+                    .iter()
+                    .take(word_length)
+                    .map(|grapheme| grapheme.len())
+                    .sum::<usize>();
+                let extra_graphemes = &typed_word[extra_offset..];
+                if !extra_graphemes.is_empty() {
+                    spans.push(Span::styled(extra_graphemes, extra_style));
+                }
+
+                if let Some(separator) = separators.get(index) {
+                    // the caret sits on the separator once the current word is fully typed
+                    let style = match typed_separators.get(index) {
+                        Some(typed) if index < current_index => {
+                            if typed.get(..1) == separator.get(..1) {
+                                correct_style
+                            } else {
+                                incorrect_style
+                            }
+                        }
+                        _ if index == current_index && typed_length >= word_length => caret_style,
+                        _ => untyped_style,
+                    };
+                    let symbol = if separator.contains('\n') { "↵" } else { " " };
+                    // the symbol stands for the separator up to the indentation
+                    let length = separator.rfind('\n').map_or(separator.len(), |i| i + 1);
+                    spans.push(Span::styled(symbol, with_ghost(style, length)));
+                }
+                spans
+            })
+            .collect()
+    }
+
     fn draw_menu_page(frame: &mut Frame, area: Rect, state: &State) {
         let chunks = View::get_chunks(area);
         let outer_layout = chunks.0;
@@ -112,7 +355,59 @@ impl View {
                 .enumerate()
                 .map(|(i, item)| {
                     let indicator = if i == menu_index as usize { "•" } else { " " };
-                    ListItem::new(format!("{} {}", indicator, item))
+                    match *item {
+                        "Mode" => ListItem::new(format!(
+                            "{} {}: ‹ {} ›",
+                            indicator,
+                            item,
+                            state.get_mode()
+                        )),
+                        "Word list" => ListItem::new(format!(
+                            "{} {}: ‹ {} ›",
+                            indicator,
+                            item,
+                            state.get_word_list_name()
+                        )),
+                        "Punctuation" => ListItem::new(format!(
+                            "{} {}: ‹ {} ›",
+                            indicator,
+                            item,
+                            if state.get_punctuation() { "on" } else { "off" }
+                        )),
+                        "Ghost" => ListItem::new(format!(
+                            "{} {}: ‹ {} ›",
+                            indicator,
+                            item,
+                            state
+                                .get_ghost_target()
+                                .map_or("off".to_string(), |target| target.to_string())
+                        )),
+                        "Hot seat" => ListItem::new(format!(
+                            "{} {}: ‹ {} ›",
+                            indicator,
+                            item,
+                            match state.get_hot_seat_size() {
+                                0 => "off".to_string(),
+                                size => format!("{size} players"),
+                            }
+                        )),
+                        "Profile" => ListItem::new(format!(
+                            "{} {}: {}",
+                            indicator,
+                            item,
+                            state
+                                .get_profile()
+                                .as_ref()
+                                .map_or(GUEST_NAME, |profile| profile.name.as_str())
+                        )),
+                        "Numbers" => ListItem::new(format!(
+                            "{} {}: ‹ {} ›",
+                            indicator,
+                            item,
+                            if state.get_numbers() { "on" } else { "off" }
+                        )),
+                        _ => ListItem::new(format!("{} {}", indicator, item)),
+                    }
                 })
                 .collect::<Vec<ListItem>>(),
         )
@@ -123,11 +418,15 @@ impl View {
         );
 
         frame.render_widget(list, outer_layout[0]);
-        View::draw_legend(
-            frame,
-            "esc: Exit, enter: Select, ↑: Up, ↓: Down",
-            inner_layout[0],
-        );
+        let mut legend = "esc: Exit, enter: Select, ↑: Up, ↓: Down, ←/→: Change option".to_string();
+        if state.get_race_player_id().is_some() {
+            let player_count = state.get_race_players().len();
+            legend += &match state.get_is_race_host() {
+                true => format!("\nrace: {player_count} players, start begins the race"),
+                false => format!("\nrace: {player_count} players, waiting for the host"),
+            };
+        }
+        View::draw_legend(frame, legend.as_str(), inner_layout[0]);
         View::draw_error(frame, state, inner_layout[1]);
     }
 
@@ -139,7 +438,7 @@ impl View {
         let title = Line::from(" typefast ");
         let widget = Paragraph::new(format!(
             "Get ready! {}s",
-            COUNTDOWN_DURATION - state.get_elapsed_time()
+            state.get_config().countdown - state.get_elapsed_time()
         ))
         .alignment(Alignment::Center)
         .block(
@@ -154,34 +453,191 @@ impl View {
         View::draw_error(frame, state, inner_layout[1]);
     }
 
-    fn draw_records_page(frame: &mut Frame, area: Rect, state: &State) {
+    fn draw_profiles_page(frame: &mut Frame, area: Rect, state: &State) {
         let chunks = View::get_chunks(area);
         let outer_layout = chunks.0;
         let inner_layout = chunks.1;
+        let profile_index = state.get_profile_index();
 
-        let title = Line::from(" typefast ");
-        let records = state.get_records();
-        let list = List::new(if records.is_empty() {
-            vec![ListItem::new("No records yet")]
-        } else {
-            records
+        let title = match state.get_hot_seat() {
+            Some(hot_seat) => format!(
+                " player {} of {}, who's next? ",
+                hot_seat.get_turn(),
+                hot_seat.player_count
+            ),
+            None => " who's typing? ".to_string(),
+        };
+        let mut items = [GUEST_NAME]
+            .into_iter()
+            .chain(
+                state
+                    .get_profiles()
+                    .iter()
+                    .map(|profile| profile.name.as_str()),
+            )
+            .enumerate()
+            .map(|(i, name)| {
+                let indicator = if i == profile_index as usize {
+                    "•"
+                } else {
+                    " "
+                };
+                ListItem::new(format!("{} {}", indicator, name))
+            })
+            .collect::<Vec<ListItem>>();
+        items.push(ListItem::new(format!(
+            "  new: {}_",
+            state.get_profile_name()
+        )));
+        let list = List::new(items).block(
+            Block::bordered()
+                .title(title)
+                .padding(Padding::new(3, 3, 1, 1)),
+        );
+
+        frame.render_widget(list, outer_layout[0]);
+        View::draw_legend(
+            frame,
+            "esc: Back, enter: Pick, ↑: Up, ↓: Down, type a name to add a profile",
+            inner_layout[0],
+        );
+        View::draw_error(frame, state, inner_layout[1]);
+    }
+
+    fn draw_leaderboard_page(frame: &mut Frame, area: Rect, state: &State) {
+        let chunks = View::get_chunks(area);
+        let outer_layout = chunks.0;
+        let inner_layout = chunks.1;
+
+        let title = Line::from(" leaderboard ");
+        let items = match state.get_hot_seat() {
+            Some(hot_seat) => hot_seat
+                .get_leaderboard()
                 .iter()
-                .map(|record| {
+                .enumerate()
+                .map(|(i, result)| {
                     ListItem::new(format!(
-                        "{} :: {} - {}wpm {}cpm",
-                        record.id, record.date, record.wpm, record.cpm
+                        "{}. {:<16} {:>4} wpm {:>6.2}% acc",
+                        i + 1,
+                        result.name,
+                        result.wpm,
+                        result.accuracy
                     ))
                 })
-                .collect::<Vec<ListItem>>()
-        })
-        .block(
+                .collect::<Vec<ListItem>>(),
+            None => Vec::new(),
+        };
+        let list = List::new(items).block(
             Block::bordered()
                 .title(title)
                 .padding(Padding::new(3, 3, 1, 1)),
         );
 
         frame.render_widget(list, outer_layout[0]);
-        View::draw_legend(frame, "esc: Exit", inner_layout[0]);
+        View::draw_legend(frame, "esc/enter: Menu", inner_layout[0]);
+        View::draw_error(frame, state, inner_layout[1]);
+    }
+
+    fn draw_records_page(frame: &mut Frame, area: Rect, state: &State) {
+        let chunks = View::get_chunks(area);
+        let outer_layout = chunks.0;
+        let inner_layout = chunks.1;
+
+        // the bests of every player sit above the records of the picked one
+        let player_bests = state.get_player_bests();
+        let records_area = match player_bests.is_empty() {
+            true => outer_layout[0],
+            false => {
+                let records_layout = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Length(player_bests.len().min(5) as u16 + 2),
+                        Constraint::Min(0),
+                    ])
+                    .split(outer_layout[0]);
+                let bests = List::new(
+                    player_bests
+                        .iter()
+                        .map(|(name, wpm)| ListItem::new(format!("{name}: {wpm} wpm")))
+                        .collect::<Vec<ListItem>>(),
+                )
+                .block(
+                    Block::bordered()
+                        .title(" bests ")
+                        .padding(Padding::horizontal(3)),
+                );
+                frame.render_widget(bests, records_layout[0]);
+                records_layout[1]
+            }
+        };
+        let title = match state.get_profile() {
+            Some(profile) => Line::from(format!(" typefast - {} ", profile.name)),
+            None => Line::from(" typefast "),
+        };
+        let block = Block::bordered()
+            .title(title)
+            .padding(Padding::new(3, 3, 1, 1));
+        let records = state.get_records();
+        if records.is_empty() {
+            frame.render_widget(Paragraph::new("No records yet").block(block), records_area);
+        } else {
+            let record_index = state.get_record_index() as usize;
+            let first_row = record_index / RECORDS_PAGE_SIZE * RECORDS_PAGE_SIZE;
+            let block = block.title(
+                Title::from(format!(
+                    " {}-{} of {} ",
+                    first_row + 1,
+                    first_row + records.len(),
+                    state.get_record_count()
+                ))
+                .alignment(Alignment::Right),
+            );
+            let arrow = match state.get_record_ascending() {
+                true => "▲",
+                false => "▼",
+            };
+            let header = Row::new(RECORD_COLUMNS.map(|column| match column {
+                _ if column == state.get_record_column() => format!("{column} {arrow}"),
+                _ => column.to_string(),
+            }))
+            .bold();
+            let best_style = Style::default().fg(state.get_config().theme.correct).bold();
+            let rows = records.iter().map(|record| {
+                let row = Row::new([
+                    record.date.clone(),
+                    record.mode.clone(),
+                    record.wpm.to_string(),
+                    format!("{:.1}%", record.accuracy),
+                    format!("{:.0}%", record.consistency),
+                ]);
+                match state.get_record_best() == Some(record.id) {
+                    true => row.style(best_style),
+                    false => row,
+                }
+            });
+            let table = Table::new(
+                rows,
+                [
+                    Constraint::Length(19),
+                    Constraint::Min(12),
+                    Constraint::Length(5),
+                    Constraint::Length(9),
+                    Constraint::Length(12),
+                ],
+            )
+            .header(header)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .block(block);
+            let mut table_state = TableState::default()
+                .with_selected(Some((record_index - first_row).min(records.len() - 1)));
+            frame.render_stateful_widget(table, records_area, &mut table_state);
+        }
+
+        View::draw_legend(
+            frame,
+            "esc: Exit, up/down/pgup/pgdn: Scroll, left/right: Sort, enter: Reverse",
+            inner_layout[0],
+        );
         View::draw_error(frame, state, inner_layout[1]);
     }
 
@@ -191,24 +647,141 @@ impl View {
         let inner_layout = chunks.1;
 
         let title = Line::from(" typefast ");
-        let widget = Paragraph::new(format!(
-            "Congrats! You typed {} words and {} characters in 60s!",
-            state.get_word_count(),
-            state.get_char_count(),
-        ))
-        .alignment(Alignment::Center)
-        .block(
-            Block::bordered()
-                .title(title)
-                .padding(Padding::new(3, 3, 1, 1)),
-        )
-        .wrap(Wrap { trim: true });
+        let metrics = state.get_session().get_metrics(state.get_duration());
+        let mut lines = vec![
+            Line::from(format!(
+                "Congrats! You typed {} words and {} characters in {:.1}s!",
+                state.get_word_count(),
+                state.get_char_count(),
+                state.get_duration() as f64 / 1000.0,
+            )),
+            Line::from(match state.get_paragraph_seed() {
+                Some(seed) => format!("mode: {}  seed: {}", state.get_mode(), seed),
+                None => format!("mode: {}", state.get_mode()),
+            }),
+            Line::from(""),
+            Line::from(format!(
+                "wpm: {}  raw: {}  cpm: {}  accuracy: {:.1}%",
+                metrics.wpm, metrics.raw_wpm, metrics.cpm, metrics.accuracy,
+            )),
+            Line::from(format!(
+                "consistency: {:.1}%  burst: {} wpm  slowest word: {:.2}s",
+                metrics.consistency,
+                metrics.burst_wpm,
+                metrics.slowest_word_time as f64 / 1000.0,
+            )),
+            Line::from(format!(
+                "correct: {}  incorrect: {}  extra: {}  missed: {}",
+                state.get_correct_count(),
+                state.get_incorrect_count(),
+                state.get_extra_count(),
+                state.get_missed_count(),
+            )),
+        ];
+        if let Some(quote) = state.get_quote() {
+            let best = match state.get_quote_best() {
+                Some(best) if best >= metrics.wpm => format!("best on this quote: {} wpm", best),
+                Some(_) => "new best on this quote!".to_string(),
+                None => "first time typing this quote".to_string(),
+            };
+            lines.push(Line::from(""));
+            lines.push(Line::from(format!("— {}", quote.source)).italic());
+            lines.push(Line::from(best));
+        }
+        let block = Block::bordered()
+            .title(title)
+            .padding(Padding::new(3, 3, 1, 1));
+        let result_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(lines.len() as u16 + 1),
+                Constraint::Min(0),
+            ])
+            .split(block.inner(outer_layout[0]));
+        let widget = Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
 
-        frame.render_widget(widget, outer_layout[0]);
+        frame.render_widget(block, outer_layout[0]);
+        frame.render_widget(widget, result_layout[0]);
+        View::draw_result_chart(frame, state, result_layout[1]);
         View::draw_legend(frame, "esc: Exit, enter: Save", inner_layout[0]);
         View::draw_error(frame, state, inner_layout[1]);
     }
 
+    fn draw_result_chart(frame: &mut Frame, state: &State, area: Rect) {
+        let samples = state.get_samples();
+        let wpm_data = samples
+            .iter()
+            .map(|sample| (sample.second as f64, sample.wpm as f64))
+            .collect::<Vec<(f64, f64)>>();
+        let raw_data = samples
+            .iter()
+            .map(|sample| (sample.second as f64, sample.raw_wpm as f64))
+            .collect::<Vec<(f64, f64)>>();
+        // errors sit on the wpm line so it's visible where they slowed things down
+        let error_data = samples
+            .iter()
+            .filter(|sample| sample.errors > 0)
+            .map(|sample| (sample.second as f64, sample.wpm as f64))
+            .collect::<Vec<(f64, f64)>>();
+
+        let max_second = samples.last().map_or(1, |sample| sample.second).max(1);
+        let max_wpm = samples
+            .iter()
+            .map(|sample| sample.wpm.max(sample.raw_wpm))
+            .max()
+            .unwrap_or_default();
+        // round the top of the y axis up to the next multiple of 20
+        let max_wpm = (max_wpm / 20 + 1) * 20;
+
+        let datasets = vec![
+            Dataset::default()
+                .name("raw")
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::DarkGray))
+                .data(&raw_data),
+            Dataset::default()
+                .name("wpm")
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::Green))
+                .data(&wpm_data),
+            Dataset::default()
+                .name("errors")
+                .marker(Marker::Dot)
+                .graph_type(GraphType::Scatter)
+                .style(Style::default().fg(Color::Red))
+                .data(&error_data),
+        ];
+        let chart = Chart::new(datasets)
+            .x_axis(
+                Axis::default()
+                    .title("seconds")
+                    .style(Style::default().fg(Color::DarkGray))
+                    .bounds([0.0, max_second as f64])
+                    .labels(vec![
+                        Span::raw("0"),
+                        Span::raw((max_second / 2).to_string()),
+                        Span::raw(max_second.to_string()),
+                    ]),
+            )
+            .y_axis(
+                Axis::default()
+                    .title("wpm")
+                    .style(Style::default().fg(Color::DarkGray))
+                    .bounds([0.0, max_wpm as f64])
+                    .labels(vec![
+                        Span::raw("0"),
+                        Span::raw((max_wpm / 2).to_string()),
+                        Span::raw(max_wpm.to_string()),
+                    ]),
+            );
+
+        frame.render_widget(chart, area);
+    }
+
     fn draw_legend(frame: &mut Frame, text: &str, area: Rect) {
         let widget = Paragraph::new(text)
             .alignment(Alignment::Left)
@@ -228,7 +801,17 @@ impl View {
 
 #[cfg(test)]
 mod tests {
+    use crate::config::{CaretStyle, Theme};
+    use crate::constants::{Language, QuoteLength, RecordColumn};
+    use crate::engine::TypingSession;
+    use crate::ghost::Ghost;
+    use crate::hot_seat::{HotSeat, HotSeatResult};
+    use crate::keystroke::Keystroke;
+    use crate::profile::Profile;
+    use crate::quote::Quote;
+    use crate::race::{Player, Progress};
     use crate::record;
+    use crate::sample::Sample;
 
     use super::*;
     use ratatui::backend::TestBackend;
@@ -246,6 +829,331 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_get_word_spans() {
+        let word_spans = View::get_word_spans("ab cd ef", "ax cdd e", &Config::default(), None);
+        let contents = word_spans
+            .iter()
+            .map(|spans| {
+                spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect::<Vec<&str>>()
+            })
+            .collect::<Vec<Vec<&str>>>();
+        assert_eq!(
+            contents,
+            vec![
+                vec!["a", "b", " "],
+                vec!["c", "d", "d", " "],
+                vec!["e", "f"]
+            ]
+        );
+        assert_eq!(word_spans[0][0].style.fg, Some(Color::Green));
+        assert_eq!(word_spans[0][1].style.fg, Some(Color::Red));
+        assert_eq!(word_spans[1][2].style.fg, Some(Color::Rgb(120, 20, 20)));
+        assert!(word_spans[2][1]
+            .style
+            .add_modifier
+            .contains(Modifier::UNDERLINED));
+
+        let word_spans = View::get_word_spans("ab cd", "a cd", &Config::default(), None);
+        assert!(word_spans[0][1].style.add_modifier.contains(Modifier::DIM));
+
+        let word_spans = View::get_word_spans("ab cd", "ab", &Config::default(), None);
+        assert!(word_spans[0][2]
+            .style
+            .add_modifier
+            .contains(Modifier::UNDERLINED));
+
+        let config = Config {
+            caret: CaretStyle::Block,
+            theme: Theme {
+                correct: Color::Blue,
+                ..Theme::default()
+            },
+            ..Config::default()
+        };
+        let word_spans = View::get_word_spans("ab cd", "ab", &config, None);
+        assert_eq!(word_spans[0][0].style.fg, Some(Color::Blue));
+        assert!(word_spans[0][2]
+            .style
+            .add_modifier
+            .contains(Modifier::REVERSED));
+    }
+
+    #[test]
+    fn test_get_word_spans_with_multi_byte_input() {
+        let word_spans = View::get_word_spans(
+            "héllo 日本語 ne\u{301}",
+            "hêllo 日本語x n",
+            &Config::default(),
+            None,
+        );
+        assert_eq!(word_spans[0][1].content, "é");
+        assert_eq!(word_spans[0][1].style.fg, Some(Color::Red));
+        assert_eq!(word_spans[1][2].content, "語");
+        assert_eq!(word_spans[1][2].style.fg, Some(Color::Green));
+        assert_eq!(word_spans[1][3].content, "x");
+        assert_eq!(word_spans[1][3].style.fg, Some(Color::Rgb(120, 20, 20)));
+        assert_eq!(word_spans[2][1].content, "e\u{301}");
+        assert!(word_spans[2][1]
+            .style
+            .add_modifier
+            .contains(Modifier::UNDERLINED));
+
+        // the base letter of a combined grapheme is correct while it is being typed
+        let word_spans = View::get_word_spans("ne\u{301}", "ne", &Config::default(), None);
+        assert_eq!(word_spans[0][1].style.fg, Some(Color::Green));
+    }
+
+    fn get_buffer_lines(terminal: &Terminal<TestBackend>) -> Vec<String> {
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer.get(x, y).symbol())
+                    .collect::<String>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_draw_game_page_viewport() {
+        // 30 columns leave 22 for the paragraph inside the border and padding
+        let backend = TestBackend::new(30, 12);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let paragraph = (1..=12)
+            .map(|index| format!("word{:02}", index))
+            .collect::<Vec<String>>()
+            .join(" ");
+        let mut state = State {
+            page: Page::Game,
+            session: TypingSession {
+                paragraph: paragraph.clone(),
+                ..TypingSession::default()
+            },
+            ..State::default()
+        };
+
+        let result = View::draw(&mut terminal, &state);
+        assert!(result.is_ok());
+        let lines = get_buffer_lines(&terminal);
+        assert!(lines[2].contains("word01 word02 word03"));
+        assert!(lines[4].contains("word07 word08 word09"));
+        assert!(!lines[5].contains("word10"));
+
+        // the active third line scrolls up to the second row
+        state.session.input = paragraph[..paragraph.find("word08").unwrap()].to_string();
+        let result = View::draw(&mut terminal, &state);
+        assert!(result.is_ok());
+        let lines = get_buffer_lines(&terminal);
+        assert!(lines[2].contains("word04 word05 word06"));
+        assert!(lines[3].contains("word07 word08 word09"));
+        assert!(lines[4].contains("word10 word11 word12"));
+    }
+
+    #[test]
+    fn test_draw_game_page_with_multi_byte_paragraph() {
+        let backend = TestBackend::new(30, 12);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let state = State {
+            page: Page::Game,
+            session: TypingSession {
+                paragraph: "héllo wörld 日本語 çağ".to_string(),
+                input: "héllo wö".to_string(),
+                ..TypingSession::default()
+            },
+            ..State::default()
+        };
+
+        let result = View::draw(&mut terminal, &state);
+        assert!(result.is_ok());
+        let lines = get_buffer_lines(&terminal);
+        assert!(lines[2].contains("héllo wörld"));
+        assert!(lines.iter().any(|line| line.contains('日')));
+    }
+
+    #[test]
+    fn test_draw_game_page_code_mode() {
+        let backend = TestBackend::new(40, 12);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let state = State {
+            page: Page::Game,
+            mode: Mode::Code(Language::Rust),
+            session: TypingSession {
+                paragraph: "fn main() {\n    x();\n\n}".to_string(),
+                input: "fn main() {\n  ".to_string(),
+                ..TypingSession::default()
+            },
+            ..State::default()
+        };
+
+        let result = View::draw(&mut terminal, &state);
+        assert!(result.is_ok());
+        let lines = get_buffer_lines(&terminal);
+        assert!(lines[2].contains("fn main() {↵ "));
+        // indentation and blank lines are kept as they are
+        assert!(lines[3].contains("│       x();↵ "));
+        assert!(lines[4]
+            .trim_matches(|character| character == '│' || character == ' ')
+            .is_empty());
+
+        let elapsed_millis = state.get_elapsed_millis();
+        let ghost_position = state
+            .get_ghost()
+            .as_ref()
+            .map(|ghost| ghost.get_position(elapsed_millis));
+        let word_spans = View::get_word_spans(
+            state.get_paragraph(),
+            state.get_input(),
+            state.get_config(),
+            ghost_position,
+        );
+        assert_eq!(word_spans[2].last().unwrap().content, "↵");
+        assert_eq!(word_spans[2].last().unwrap().style.fg, Some(Color::Green));
+        // the caret waits on the indentation that's still missing
+        assert_eq!(word_spans[3][1].style.fg, Some(Color::Green));
+        assert!(word_spans[3][2]
+            .style
+            .add_modifier
+            .contains(Modifier::UNDERLINED));
+        assert!(!word_spans[3][4]
+            .style
+            .add_modifier
+            .contains(Modifier::UNDERLINED));
+    }
+
+    #[test]
+    fn test_get_word_spans_with_ghost() {
+        let config = Config::default();
+        let word_spans = View::get_word_spans("ab cd", "a", &config, Some(1));
+        assert_eq!(word_spans[0][1].style.bg, Some(Color::Cyan));
+        assert_eq!(word_spans[0][0].style.bg, None);
+        let word_spans = View::get_word_spans("ab cd", "a", &config, Some(2));
+        assert_eq!(word_spans[0][2].content, " ");
+        assert_eq!(word_spans[0][2].style.bg, Some(Color::Cyan));
+
+        // the line break and the indentation after it are separate positions
+        let word_spans = View::get_word_spans("{\n  x", "", &config, Some(2));
+        assert_eq!(word_spans[0][1].style.bg, None);
+        assert_eq!(word_spans[1][0].style.bg, Some(Color::Cyan));
+        let word_spans = View::get_word_spans("{\n  x", "", &config, Some(4));
+        assert_eq!(word_spans[1][2].content, "x");
+        assert_eq!(word_spans[1][2].style.bg, Some(Color::Cyan));
+    }
+
+    #[test]
+    fn test_draw_game_page_with_ghost() {
+        let backend = TestBackend::new(120, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let keystrokes = "some"
+            .chars()
+            .enumerate()
+            .map(|(index, typed)| Keystroke {
+                timestamp: index as i64 * 100,
+                typed,
+                ..Keystroke::default()
+            })
+            .collect::<Vec<Keystroke>>();
+        let state = State {
+            page: Page::Game,
+            session: TypingSession {
+                paragraph: "some words".to_string(),
+                input: "so".to_string(),
+                position: 2,
+                ..TypingSession::default()
+            },
+            ghost: Some(Ghost::new(
+                record::Record::default(),
+                &keystrokes,
+                "some words",
+            )),
+            ..State::default()
+        };
+
+        let result = View::draw(&mut terminal, &state);
+        assert!(result.is_ok());
+        let lines = get_buffer_lines(&terminal);
+        // the run hasn't started so the ghost is still at its first keystroke
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("ghost: +1 chars +0.1s")));
+    }
+
+    #[test]
+    fn test_draw_game_page_with_race() {
+        let backend = TestBackend::new(120, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let player = |id: usize, name: &str, position: usize, finished: bool| Player {
+            id,
+            name: name.to_string(),
+            progress: Progress {
+                position,
+                wpm: 40,
+                finished,
+            },
+        };
+        let state = State {
+            page: Page::Game,
+            session: TypingSession {
+                paragraph: "some words".to_string(),
+                ..TypingSession::default()
+            },
+            race_player_id: Some(0),
+            race_players: vec![player(0, "ana", 5, false), player(1, "bo", 10, true)],
+            ..State::default()
+        };
+
+        let result = View::draw(&mut terminal, &state);
+        assert!(result.is_ok());
+        let lines = get_buffer_lines(&terminal);
+        assert!(lines.iter().any(|line| line.contains("bo ✓")));
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("██████░░░░░░ 40 wpm")));
+        assert!(lines.iter().any(|line| line.contains("some words")));
+    }
+
+    #[test]
+    fn test_get_progress_bar() {
+        assert_eq!(View::get_progress_bar(0, 10), "░".repeat(RACE_BAR_WIDTH));
+        assert_eq!(View::get_progress_bar(10, 10), "█".repeat(RACE_BAR_WIDTH));
+        assert_eq!(View::get_progress_bar(3, 0), "░".repeat(RACE_BAR_WIDTH));
+    }
+
+    #[test]
+    fn test_draw_game_page_with_input() {
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let state = State {
+            page: Page::Game,
+            session: TypingSession {
+                paragraph: "some words to type".to_string(),
+                input: "sone wordsss t".to_string(),
+                ..TypingSession::default()
+            },
+            ..State::default()
+        };
+
+        let result = View::draw(&mut terminal, &state);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_draw_game_page_words_mode() {
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let state = State {
+            page: Page::Game,
+            mode: Mode::Words(10),
+            ..State::default()
+        };
+
+        let result = View::draw(&mut terminal, &state);
+        assert!(result.is_ok());
+    }
+
     #[test]
     fn test_draw_menu_page() {
         let backend = TestBackend::new(80, 24);
@@ -272,6 +1180,53 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_draw_game_result_page_with_quote() {
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut state = State {
+            page: Page::GameResult,
+            mode: Mode::Quote(QuoteLength::Short),
+            quote: Some(Quote {
+                id: 1,
+                text: "Brevity is the soul of wit.".to_string(),
+                source: "William Shakespeare, Hamlet".to_string(),
+            }),
+            session: TypingSession {
+                char_count: 27,
+                ..TypingSession::default()
+            },
+            duration: 6000,
+            ..State::default()
+        };
+
+        let result = View::draw(&mut terminal, &state);
+        assert!(result.is_ok());
+        let lines = get_buffer_lines(&terminal);
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("— William Shakespeare, Hamlet")));
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("first time typing this quote")));
+
+        state.quote_best = Some(80);
+        let result = View::draw(&mut terminal, &state);
+        assert!(result.is_ok());
+        let lines = get_buffer_lines(&terminal);
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("best on this quote: 80 wpm")));
+
+        state.quote_best = Some(40);
+        let result = View::draw(&mut terminal, &state);
+        assert!(result.is_ok());
+        let lines = get_buffer_lines(&terminal);
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("new best on this quote!")));
+    }
+
     #[test]
     fn test_draw_records_page() {
         let backend = TestBackend::new(80, 24);
@@ -281,6 +1236,18 @@ mod tests {
             wpm: 35,
             cpm: 260,
             date: "2025-01-04 14:07:25".to_string(),
+            raw_wpm: 38,
+            accuracy: 96.5,
+            mode: "time 60".to_string(),
+            consistency: 78.4,
+            burst_wpm: 49,
+            slowest_word_time: 1250,
+            quote_id: None,
+            seed: None,
+            player_id: None,
+            word_list: None,
+            punctuation: false,
+            numbers: false,
         }];
         let state = State {
             page: Page::Records,
@@ -292,6 +1259,127 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_draw_records_page_with_bests() {
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let state = State {
+            page: Page::Records,
+            profile: Some(Profile {
+                id: 1,
+                name: "ana".to_string(),
+            }),
+            player_bests: vec![("bo".to_string(), 80), ("ana".to_string(), 70)],
+            ..State::default()
+        };
+
+        let result = View::draw(&mut terminal, &state);
+        assert!(result.is_ok());
+        let lines = get_buffer_lines(&terminal);
+        assert!(lines.iter().any(|line| line.contains("bo: 80 wpm")));
+        assert!(lines.iter().any(|line| line.contains("typefast - ana")));
+    }
+
+    #[test]
+    fn test_draw_records_table() {
+        let backend = TestBackend::new(100, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let records = (1..=3)
+            .map(|id| record::Record {
+                id,
+                wpm: 40 + id as i64,
+                date: format!("2025-01-0{id} 10:00:00"),
+                mode: "words 25".to_string(),
+                accuracy: 97.5,
+                consistency: 80.0,
+                ..record::Record::default()
+            })
+            .collect();
+        let state = State {
+            page: Page::Records,
+            records,
+            record_count: 23,
+            record_index: 11,
+            record_column: RecordColumn::Wpm,
+            record_best: Some(3),
+            ..State::default()
+        };
+
+        let result = View::draw(&mut terminal, &state);
+        assert!(result.is_ok());
+        let lines = get_buffer_lines(&terminal);
+        assert!(lines.iter().any(|line| line.contains("11-13 of 23")));
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("date") && line.contains("wpm ▼")));
+        assert!(lines.iter().any(|line| line.contains("2025-01-03 10:00:00")
+            && line.contains("43")
+            && line.contains("97.5%")
+            && line.contains("80%")));
+
+        // the personal best is drawn in the correct color, the cursor row reversed
+        let buffer = terminal.backend().buffer();
+        let row_of = |text: &str| lines.iter().position(|line| line.contains(text)).unwrap();
+        let best_cell = buffer.get(10, row_of("2025-01-03") as u16);
+        assert_eq!(best_cell.fg, Config::default().theme.correct);
+        let cursor_cell = buffer.get(10, row_of("2025-01-02") as u16);
+        assert!(cursor_cell.modifier.contains(Modifier::REVERSED));
+    }
+
+    #[test]
+    fn test_draw_profiles_page() {
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let state = State {
+            page: Page::Profiles,
+            profiles: vec![Profile {
+                id: 1,
+                name: "ana".to_string(),
+            }],
+            profile_index: 1,
+            profile_name: "bo".to_string(),
+            hot_seat: Some(HotSeat::new(3, 7, Mode::Words(10))),
+            ..State::default()
+        };
+
+        let result = View::draw(&mut terminal, &state);
+        assert!(result.is_ok());
+        let lines = get_buffer_lines(&terminal);
+        assert!(lines.iter().any(|line| line.contains("player 1 of 3")));
+        assert!(lines.iter().any(|line| line.contains("• ana")));
+        assert!(lines.iter().any(|line| line.contains("new: bo_")));
+    }
+
+    #[test]
+    fn test_draw_leaderboard_page() {
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut hot_seat = HotSeat::new(2, 7, Mode::Words(10));
+        hot_seat.results = vec![
+            HotSeatResult {
+                name: "ana".to_string(),
+                wpm: 60,
+                accuracy: 95.0,
+            },
+            HotSeatResult {
+                name: "bo".to_string(),
+                wpm: 72,
+                accuracy: 90.0,
+            },
+        ];
+        let state = State {
+            page: Page::Leaderboard,
+            hot_seat: Some(hot_seat),
+            ..State::default()
+        };
+
+        let result = View::draw(&mut terminal, &state);
+        assert!(result.is_ok());
+        let lines = get_buffer_lines(&terminal);
+        assert!(lines.iter().any(|line| line.contains("1. bo")));
+        assert!(lines.iter().any(|line| line.contains("2. ana")));
+    }
+
     #[test]
     fn test_draw_empty_records_page() {
         let backend = TestBackend::new(80, 24);
@@ -307,15 +1395,60 @@ mod tests {
     }
 
     #[test]
-    fn test_draw_game_result_page() {
+    fn test_draw_game_result_page_with_samples() {
         let backend = TestBackend::new(80, 24);
         let mut terminal = Terminal::new(backend).unwrap();
+        let samples = (1..=15)
+            .map(|second| Sample {
+                second,
+                wpm: 40 + second,
+                raw_wpm: 50 - second,
+                errors: (second % 4 == 0) as u64,
+            })
+            .collect();
         let state = State {
+            page: Page::GameResult,
+            session: TypingSession {
+                samples,
+                ..TypingSession::default()
+            },
+            ..State::default()
+        };
+
+        let result = View::draw(&mut terminal, &state);
+        assert!(result.is_ok());
+        let content = terminal
+            .backend()
+            .buffer()
+            .content
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect::<String>();
+        assert!(content.contains("seconds"));
+        assert!(content.contains("•"));
+    }
+
+    #[test]
+    fn test_draw_game_result_page() {
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut state = State {
             page: Page::GameResult,
             ..State::default()
         };
 
         let result = View::draw(&mut terminal, &state);
         assert!(result.is_ok());
+        let lines = get_buffer_lines(&terminal);
+        assert!(!lines.iter().any(|line| line.contains("seed:")));
+
+        state.mode = Mode::Daily;
+        state.paragraph_seed = Some(20240131);
+        let result = View::draw(&mut terminal, &state);
+        assert!(result.is_ok());
+        let lines = get_buffer_lines(&terminal);
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("mode: daily  seed: 20240131")));
     }
 }